@@ -2,7 +2,6 @@
 use ethers::abi::{Abi, Token, Function};
 use ethers::types::{U256, Address, Bytes};
 use ethers::utils::hex::encode as to_hex;
-use ethers::contract::AbiError;
 use serde_json::{Value, json};
 use std::fs;
 use std::sync::Arc;
@@ -13,6 +12,7 @@ use futures::FutureExt;
 use crate::utils::utils::{get_replaced_addresses, get_random_proxy_client};
 use crate::utils::format_swap_details::format_swap_details;
 use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
 use tracing::{debug, error};
 use url::Url;
 
@@ -33,23 +33,71 @@ lazy_static::lazy_static! {
     static ref ACROSS_ABI: AcrossAbi = AcrossAbi::new(PathBuf::from("./src/dapps/abi/across/abi.json"));
 }
 
-pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static, Result<Value, String>> {
+// One requested deposit, before the Across API has priced it
+#[derive(Clone, Copy)]
+struct DepositRoute<'a> {
+    input_token: &'a str,
+    output_token: &'a str,
+    origin_chain_id: u64,
+    destination_chain_id: u64,
+    amount: &'a str,
+    depositor: &'a str,
+    recipient: &'a str,
+}
+
+// Arguments of SpokePool.depositV3, as the request and the suggested-fees response give them
+pub struct DepositV3Params<'a> {
+    pub depositor: &'a str,
+    pub recipient: &'a str,
+    pub input_token: &'a str,
+    pub output_token: &'a str,
+    pub input_amount: &'a str,
+    pub output_amount: &'a str,
+    pub destination_chain_id: u64,
+    pub exclusive_relayer: &'a str,
+    pub quote_timestamp: u32,
+    pub fill_deadline: u32,
+    pub exclusivity_deadline: u32,
+}
+
+pub struct Across;
+
+impl DappAdapter for Across {
+    fn name(&self) -> &'static str {
+        "across"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Bridge)
+    }
+
+    fn error_abis(&self) -> Vec<&'static Abi> {
+        vec![&ACROSS_ABI.abi]
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
     async move {
+        let params = &request.params;
         debug!("Starting get_swap_quote with params: {:?}", params);
 
-        let from_chain_id = params["fromChainId"].as_u64().ok_or("Invalid fromChainId")?;
-        let amount = params["amount"].as_str().ok_or("Invalid amount")?;
-        let from_token_address = params["fromTokenAddress"].as_str().ok_or("Invalid fromTokenAddress")?;
-        let to_token_address = params["toTokenAddress"].as_str().ok_or("Invalid toTokenAddress")?;
-        let to_address = params["toAddress"].as_str().ok_or("Invalid toAddress")?;
-        let to_chain_id = params["toChainId"].as_u64().ok_or("Invalid toChainId")?;
-        let from_address = params["fromAddress"].as_str().ok_or("Invalid fromAddress")?;
-        let quote_only = params["quoteOnly"].as_bool().unwrap_or(false);
+        let from_chain_id = request.from_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let to_chain_id = request.to_chain_id;
+        let from_address = request.from_address.as_str();
+        let quote_only = request.quote_only;
 
         debug!("Extracted parameters: from_chain_id={}, to_chain_id={}, amount={}, quote_only={}", 
                from_chain_id, to_chain_id, amount, quote_only);
 
-        let (valid_from_token_address, _valid_to_token_address) = get_replaced_addresses(
+        let (valid_from_token_address, valid_to_token_address) = get_replaced_addresses(
             from_token_address,
             to_token_address,
             from_chain_id,
@@ -57,23 +105,20 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
             "across",
             &state
         ).map_err(|e| format!("Failed to replace addresses: {}", e))?;
-        debug!("Replaced addresses: valid_from_token_address={}", valid_from_token_address);
+        debug!("Replaced addresses: valid_from_token_address={}, valid_to_token_address={}", valid_from_token_address, valid_to_token_address);
 
-        let full_quote_result = fetch_data_and_calculate(
-            &valid_from_token_address,
-            to_chain_id,
+        let route = DepositRoute {
+            input_token: &valid_from_token_address,
+            output_token: &valid_to_token_address,
+            origin_chain_id: from_chain_id,
+            destination_chain_id: to_chain_id,
             amount,
-            from_chain_id,
-            to_address,
-            &state
-        ).await?;
+            depositor: from_address,
+            recipient: to_address,
+        };
+        let full_quote_result = fetch_data_and_calculate(&route, &state).await?;
         debug!("Fetched full quote result: {:?}", full_quote_result);
 
-        if !full_quote_result["success"].as_bool().unwrap_or(false) {
-            error!("Failed to retrieve or process quote: {:?}", full_quote_result);
-            return Err(full_quote_result["message"].as_str().unwrap_or("Unable to retrieve or process quote").to_string());
-        }
-
         let zero_address = "0x0000000000000000000000000000000000000000";
         let eee_address = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
         let value = if from_token_address == zero_address || from_token_address == eee_address {
@@ -85,6 +130,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
         let transaction_data = full_quote_result["data"]["transactionData"].clone();
         let amount_out = full_quote_result["data"]["amountOut"].clone();
         let spoke_pool_address = full_quote_result["data"]["spokePoolAddress"].clone();
+        let dapp_options = json!({ "estimatedTime": full_quote_result["data"]["expectedFillTimeSec"] });
 
         let formatted_result = if quote_only {
             let transaction_data_basic = json!({
@@ -98,14 +144,14 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
 
             format_swap_details(
                 "across",
-                &params,
+                params,
                 &transaction_data_basic,
                 &amount_out,
                 &spoke_pool_address,
                 &params["gasPrices"],
                 Some("quote"),
                 None,
-                None,
+                Some(&dapp_options),
                 &state
             )
             .await
@@ -121,14 +167,14 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
 
             format_swap_details(
                 "across",
-                &params,
+                params,
                 &transaction_data_full,
                 &amount_out,
                 &spoke_pool_address,
                 &params["gasPrices"],
                 None,
                 None,
-                None,
+                Some(&dapp_options),
                 &state
             )
             .await
@@ -139,12 +185,13 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
             Err(e) => error!("Error formatting swap details: {}", e),
         }
 
-        formatted_result.map_err(|e| format!("Error formatting swap details: {}", e))
+        formatted_result.map_err(|e| e.context("Error formatting swap details"))
     }.boxed()
 }
 
 async fn fetch_suggested_fees(
-    token: &str,
+    input_token: &str,
+    output_token: &str,
     destination_chain_id: u64,
     amount: &str,
     origin_chain_id: u64,
@@ -154,7 +201,8 @@ async fn fetch_suggested_fees(
     let mut url = Url::parse("https://app.across.to/api/suggested-fees").map_err(|e| e.to_string())?;
 
     url.query_pairs_mut()
-        .append_pair("token", token)
+        .append_pair("inputToken", input_token)
+        .append_pair("outputToken", output_token)
         .append_pair("destinationChainId", &destination_chain_id.to_string())
         .append_pair("amount", amount)
         .append_pair("originChainId", &origin_chain_id.to_string())
@@ -193,7 +241,8 @@ async fn fetch_suggested_fees(
 }
 
 async fn fetch_limits(
-    token: &str,
+    input_token: &str,
+    output_token: &str,
     destination_chain_id: u64,
     origin_chain_id: u64,
     state: &Arc<AppState>
@@ -201,7 +250,8 @@ async fn fetch_limits(
     let mut url = Url::parse("https://app.across.to/api/limits").map_err(|e| e.to_string())?;
 
     url.query_pairs_mut()
-        .append_pair("token", token)
+        .append_pair("inputToken", input_token)
+        .append_pair("outputToken", output_token)
         .append_pair("destinationChainId", &destination_chain_id.to_string())
         .append_pair("originChainId", &origin_chain_id.to_string());
 
@@ -235,36 +285,41 @@ async fn fetch_limits(
     Ok(json)
 }
 
-async fn fetch_data_and_calculate(
-    token: &str,
-    destination_chain_id: u64,
-    amount: &str,
-    origin_chain_id: u64,
-    recipient: &str,
-    state: &Arc<AppState>
-) -> Result<Value, String> {
-    debug!("Fetching data and calculating: token={}, destination_chain_id={}, amount={}, origin_chain_id={}, recipient={}",
-           token, destination_chain_id, amount, origin_chain_id, recipient);
+async fn fetch_data_and_calculate(route: &DepositRoute<'_>, state: &Arc<AppState>) -> Result<Value, QuoteError> {
+    let DepositRoute { input_token, output_token, origin_chain_id, destination_chain_id, amount, depositor, recipient } = *route;
+    debug!("Fetching data and calculating: input_token={}, output_token={}, destination_chain_id={}, amount={}, origin_chain_id={}, recipient={}",
+           input_token, output_token, destination_chain_id, amount, origin_chain_id, recipient);
 
-    let suggested_fees = fetch_suggested_fees(token, destination_chain_id, amount, origin_chain_id, recipient, state).await
-        .map_err(|e| format!("Failed to fetch suggested fees: {}", e))?;
+    let suggested_fees = fetch_suggested_fees(input_token, output_token, destination_chain_id, amount, origin_chain_id, recipient, state).await
+        .map_err(|e| QuoteError::upstream(format!("Failed to fetch suggested fees: {}", e)))?;
     
     debug!("Suggested fees response: {:?}", suggested_fees);
 
     let suggested_fees = suggested_fees.as_object()
         .ok_or_else(|| "Suggested fees response is not an object".to_string())?;
 
-    let relay_fee_total = suggested_fees.get("relayFeeTotal")
+    let relay_fee_total = suggested_fees.get("totalRelayFee")
+        .and_then(|v| v.get("total"))
+        .or_else(|| suggested_fees.get("relayFeeTotal"))
         .and_then(|v| v.as_str())
-        .ok_or_else(|| "Missing relayFeeTotal".to_string())?;
+        .ok_or_else(|| "Missing totalRelayFee".to_string())?;
 
     let spoke_pool_address = suggested_fees.get("spokePoolAddress")
         .and_then(|v| v.as_str())
         .ok_or_else(|| "Missing spokePoolAddress".to_string())?;
 
-    let relay_fee_pct_str = suggested_fees.get("relayFeePct")
+    // Nobody gets exclusivity when the API doesn't name a relayer
+    let exclusive_relayer = suggested_fees.get("exclusiveRelayer")
         .and_then(|v| v.as_str())
-        .ok_or_else(|| "Missing relayFeePct".to_string())?;
+        .unwrap_or("0x0000000000000000000000000000000000000000");
+
+    let exclusivity_deadline = suggested_fees.get("exclusivityDeadline")
+        .and_then(parse_u32_field)
+        .unwrap_or(0);
+
+    let fill_deadline = suggested_fees.get("fillDeadline")
+        .and_then(parse_u32_field)
+        .ok_or_else(|| "Missing fillDeadline".to_string())?;
 
     let timestamp_str = suggested_fees.get("timestamp")
         .and_then(|v| v.as_str())
@@ -275,10 +330,18 @@ async fn fetch_data_and_calculate(
     let relay_fee_total_u256 = U256::from_dec_str(relay_fee_total)
         .map_err(|e| format!("Invalid relayFeeTotal: {}", e))?;
 
-    let amount_out = (amount_u256 - relay_fee_total_u256).to_string();
+    if relay_fee_total_u256 >= amount_u256 {
+        return Err(QuoteError::out_of_limits("Amount is too small to cover relay fees."));
+    }
+
+    // Newer API versions quote the output amount directly, which also covers output tokens with other decimals
+    let amount_out = match suggested_fees.get("outputAmount").and_then(|v| v.as_str()) {
+        Some(output_amount) => output_amount.to_string(),
+        None => (amount_u256 - relay_fee_total_u256).to_string(),
+    };
 
-    let limits = fetch_limits(token, destination_chain_id, origin_chain_id, state).await
-        .map_err(|e| format!("Failed to fetch limits: {}", e))?;
+    let limits = fetch_limits(input_token, output_token, destination_chain_id, origin_chain_id, state).await
+        .map_err(|e| QuoteError::upstream(format!("Failed to fetch limits: {}", e)))?;
     
     debug!("Limits response: {:?}", limits);
 
@@ -301,31 +364,30 @@ async fn fetch_data_and_calculate(
     let is_amount_within_limits = amount_u256 > min_deposit_u256 && amount_u256 < max_deposit_instant_u256;
 
     if !is_amount_within_limits {
-        return Ok(json!({
-            "success": false,
-            "message": "Amount is out of limits."
-        }));
+        return Err(QuoteError::out_of_limits("Amount is out of limits."));
     }
 
-    // Convert the relay fee percentage and timestamp from &str to i64 and u32 respectively
-    let relay_fee_pct: i64 = relay_fee_pct_str.parse().map_err(|_| "Invalid relay fee percentage")?;
     let timestamp: u32 = timestamp_str.parse().map_err(|_| "Invalid timestamp")?;
 
     // Pass the ABI instead of provider.clone()
-    let transaction_data = match generate_transaction_data(
+    let deposit = DepositV3Params {
+        depositor,
         recipient,
-        token,
-        amount,
+        input_token,
+        output_token,
+        input_amount: amount,
+        output_amount: &amount_out,
         destination_chain_id,
-        relay_fee_pct,
-        timestamp,
-        spoke_pool_address,
-        &ACROSS_ABI.abi // Correct ABI passed here
-    ).await {
+        exclusive_relayer,
+        quote_timestamp: timestamp,
+        fill_deadline,
+        exclusivity_deadline,
+    };
+    let transaction_data = match generate_transaction_data(&deposit, &ACROSS_ABI.abi).await {
         Ok(data) => data,
         Err(e) => {
             error!("Failed to generate transaction data: {}", e);
-            return Err(format!("Failed to generate transaction data: {}", e));
+            return Err(format!("Failed to generate transaction data: {}", e).into());
         }
     };
 
@@ -334,49 +396,96 @@ async fn fetch_data_and_calculate(
         "data": {
             "transactionData": transaction_data,
             "amountOut": amount_out,
-            "spokePoolAddress": spoke_pool_address
+            "spokePoolAddress": spoke_pool_address,
+            "expectedFillTimeSec": suggested_fees.get("expectedFillTimeSec").and_then(parse_u32_field)
         }
     }))
 }
-pub async fn generate_transaction_data(
-    recipient: &str,
-    origin_token: &str,
-    amount: &str,
-    destination_chain_id: u64,
-    relayer_fee_pct: i64,
-    quote_timestamp: u32,
-    _spoke_pool_address: &str,
-    abi: &Abi, // Pass the ABI here
-) -> Result<String, AbiError> {
-
+// The API returns deadlines as numbers or numeric strings depending on the version
+fn parse_u32_field(value: &Value) -> Option<u32> {
+    value.as_u64()
+        .map(|v| v as u32)
+        .or_else(|| value.as_str().and_then(|v| v.parse().ok()))
+}
 
-    // Parse recipient and origin token addresses
-    let recipient_address = Address::from_str(recipient).expect("Invalid recipient address");
-    let origin_token_address = Address::from_str(origin_token).expect("Invalid origin token address");
+pub async fn generate_transaction_data(deposit: &DepositV3Params<'_>, abi: &Abi) -> Result<String, String> {
+    // Parse the depositor, recipient, token and relayer addresses, some of which come from the Across API
+    let depositor_address = Address::from_str(deposit.depositor).map_err(|e| format!("Invalid depositor address: {}", e))?;
+    let recipient_address = Address::from_str(deposit.recipient).map_err(|e| format!("Invalid recipient address: {}", e))?;
+    let input_token_address = Address::from_str(deposit.input_token).map_err(|e| format!("Invalid input token address: {}", e))?;
+    let output_token_address = Address::from_str(deposit.output_token).map_err(|e| format!("Invalid output token address: {}", e))?;
+    let exclusive_relayer_address = Address::from_str(deposit.exclusive_relayer).map_err(|e| format!("Invalid exclusive relayer address: {}", e))?;
 
-    // Parse amount to U256
-    let amount_u256 = U256::from_dec_str(amount).expect("Invalid amount");
+    // Parse amounts to U256
+    let input_amount_u256 = U256::from_dec_str(deposit.input_amount).map_err(|e| format!("Invalid input amount: {}", e))?;
+    let output_amount_u256 = U256::from_dec_str(deposit.output_amount).map_err(|e| format!("Invalid output amount: {}", e))?;
 
-    // Get the "deposit" function from the ABI
-    let function: &Function = abi.function("deposit")?;
+    // Get the "depositV3" function from the ABI
+    let function: &Function = abi.function("depositV3").map_err(|e| format!("Missing depositV3 in ABI: {}", e))?;
 
     // Prepare the parameters for the contract method call
     let params = vec![
+        Token::Address(depositor_address),
         Token::Address(recipient_address),
-        Token::Address(origin_token_address),
-        Token::Uint(amount_u256),
-        Token::Uint(U256::from(destination_chain_id)),
-        Token::Int(relayer_fee_pct.into()),
-        Token::Uint(U256::from(quote_timestamp)),
+        Token::Address(input_token_address),
+        Token::Address(output_token_address),
+        Token::Uint(input_amount_u256),
+        Token::Uint(output_amount_u256),
+        Token::Uint(U256::from(deposit.destination_chain_id)),
+        Token::Address(exclusive_relayer_address),
+        Token::Uint(U256::from(deposit.quote_timestamp)),
+        Token::Uint(U256::from(deposit.fill_deadline)),
+        Token::Uint(U256::from(deposit.exclusivity_deadline)),
         Token::Bytes(vec![]), // Empty message
-        Token::Uint(U256::MAX), // Max uint256 value
     ];
 
-    // Encode the function data for the "deposit" function
-    let data: Bytes = function.encode_input(&params)?.into();
+    // Encode the function data for the "depositV3" function
+    let data: Bytes = function.encode_input(&params).map_err(|e| format!("Failed to encode depositV3: {}", e))?.into();
 
     // Convert the data to hex string in the "0x..." format
     let hex_data = format!("0x{}", to_hex(data));
 
     Ok(hex_data)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deposit<'a>(recipient: &'a str) -> DepositV3Params<'a> {
+        DepositV3Params {
+            depositor: "0x1111111111111111111111111111111111111111",
+            recipient,
+            input_token: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+            output_token: "0xaf88d065e77c8cC2239327C5EDb3A432268e5831",
+            input_amount: "1000000",
+            output_amount: "998000",
+            destination_chain_id: 42161,
+            exclusive_relayer: "0x0000000000000000000000000000000000000000",
+            quote_timestamp: 1_700_000_000,
+            fill_deadline: 1_700_021_600,
+            exclusivity_deadline: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn encodes_deposit_v3_arguments_in_order() {
+        let data = generate_transaction_data(&deposit("0x2222222222222222222222222222222222222222"), &ACROSS_ABI.abi).await.unwrap();
+        let data = Bytes::from_str(&data).unwrap();
+        let function = ACROSS_ABI.abi.function("depositV3").unwrap();
+        assert_eq!(data[..4], function.short_signature());
+
+        let tokens = function.decode_input(&data[4..]).unwrap();
+        assert_eq!(tokens[1], Token::Address(Address::from_str("0x2222222222222222222222222222222222222222").unwrap()));
+        assert_eq!(tokens[4], Token::Uint(U256::from(1_000_000u64)));
+        assert_eq!(tokens[5], Token::Uint(U256::from(998_000u64)));
+        assert_eq!(tokens[6], Token::Uint(U256::from(42161u64)));
+        assert_eq!(tokens[9], Token::Uint(U256::from(1_700_021_600u64)));
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_api_addresses() {
+        let error = generate_transaction_data(&deposit("not an address"), &ACROSS_ABI.abi).await.unwrap_err();
+        assert!(error.starts_with("Invalid recipient address"));
+    }
+}
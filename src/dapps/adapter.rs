@@ -0,0 +1,303 @@
+use ethers::abi::Abi;
+use ethers::types::{Signature, U256};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::sync::Arc;
+use futures::future::BoxFuture;
+use crate::load_resources::AppState;
+
+// Quote parameters parsed once by the router instead of in every adapter
+#[derive(Debug, Clone)]
+pub struct QuoteRequest {
+    pub from_chain_id: u64,
+    pub to_chain_id: u64,
+    // Exact input amount, empty for exact-output quotes
+    pub amount: String,
+    // Exact output amount requested instead of `amount`
+    pub to_amount: Option<String>,
+    pub from_token_address: String,
+    pub to_token_address: String,
+    pub from_address: String,
+    pub to_address: String,
+    // Percent, e.g. 0.5 for 0.5%
+    pub slippage: f64,
+    pub quote_only: bool,
+    // Signed permit sent back to build-transaction, used instead of an on-chain approve
+    pub permit: Option<PermitSignature>,
+    // The extended params (gas prices, token details, options) for format_swap_details
+    pub params: Value,
+}
+
+impl QuoteRequest {
+    pub fn from_params(params: Value) -> Result<Self, String> {
+        let from_chain_id = params["fromChainId"].as_u64().ok_or("Invalid fromChainId")?;
+        let from_address = params["fromAddress"].as_str().ok_or("Invalid fromAddress")?.to_string();
+        let options = params["options"].as_object().ok_or("Invalid options")?;
+        let slippage = options.get("slippage")
+            .and_then(|s| s.as_str().and_then(|s| s.parse::<f64>().ok()).or_else(|| s.as_f64()))
+            .ok_or("Invalid slippage")?;
+        let amount = params["amount"].as_str().unwrap_or_default().to_string();
+        let to_amount = params["toAmount"].as_str().filter(|a| !a.is_empty()).map(|a| a.to_string());
+        if amount.is_empty() && to_amount.is_none() {
+            return Err("Invalid amount".to_string());
+        }
+
+        Ok(QuoteRequest {
+            from_chain_id,
+            to_chain_id: params["toChainId"].as_u64().unwrap_or(from_chain_id),
+            amount,
+            to_amount,
+            from_token_address: params["fromTokenAddress"].as_str().ok_or("Invalid fromTokenAddress")?.to_string(),
+            to_token_address: params["toTokenAddress"].as_str().ok_or("Invalid toTokenAddress")?.to_string(),
+            to_address: params["toAddress"].as_str().map(|a| a.to_string()).unwrap_or_else(|| from_address.clone()),
+            from_address,
+            slippage,
+            quote_only: params["quoteOnly"].as_bool().unwrap_or(false),
+            permit: None,
+            params,
+        })
+    }
+
+    pub fn is_cross_chain(&self) -> bool {
+        self.from_chain_id != self.to_chain_id
+    }
+
+    pub fn is_exact_out(&self) -> bool {
+        self.to_amount.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermitKind {
+    // The token's own permit(owner, spender, value, deadline, v, r, s)
+    Eip2612,
+}
+
+// A permit signature checked against the owner, ready for an adapter to encode
+#[derive(Debug, Clone)]
+pub struct PermitSignature {
+    pub kind: PermitKind,
+    pub value: U256,
+    pub nonce: U256,
+    pub deadline: U256,
+    pub signature: Signature,
+}
+
+impl PermitSignature {
+    // v, r, s as token permit functions take them, with v normalized to 27/28
+    pub fn split(&self) -> (u8, [u8; 32], [u8; 32]) {
+        let v = if self.signature.v < 27 { self.signature.v + 27 } else { self.signature.v };
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        self.signature.r.to_big_endian(&mut r);
+        self.signature.s.to_big_endian(&mut s);
+        (v as u8, r, s)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteTransaction {
+    pub value: String,
+    pub to: String,
+    pub from: String,
+    pub data: String,
+    pub chain_id: u64,
+    pub gas_price: String,
+    pub gas: String,
+    // Normal-tier EIP-1559 fees, absent on legacy chains
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_gas: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas: Option<String>,
+}
+
+// The quote shape every adapter must produce; optional fields ride along in `extra`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteResponse {
+    pub tool: String,
+    pub from_chain_id: u64,
+    pub to_chain_id: u64,
+    pub from_amount: String,
+    pub to_amount: String,
+    pub to_amount_min: String,
+    pub from_token: Value,
+    pub to_token: Value,
+    pub transaction: QuoteTransaction,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl QuoteResponse {
+    pub fn from_value(value: Value) -> Result<Self, String> {
+        serde_json::from_value(value).map_err(|e| format!("Invalid quote response: {}", e))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteErrorKind {
+    // The router skipped the dapp: it isn't enabled for this route or can't quote this mode
+    Unsupported,
+    Timeout,
+    UnsupportedPair,
+    AmountOutOfLimits,
+    UpstreamHttp,
+    GasEstimationFailed,
+    // The transaction failed calldata verification and was withheld
+    UnsafeCalldata,
+    Other,
+}
+
+// Why an adapter couldn't quote; plain string errors convert into `Other`
+#[derive(Debug, Clone)]
+pub struct QuoteError {
+    pub kind: QuoteErrorKind,
+    pub message: String,
+}
+
+impl QuoteError {
+    pub fn new(kind: QuoteErrorKind, message: impl Into<String>) -> Self {
+        QuoteError { kind, message: message.into() }
+    }
+
+    pub fn unsupported_pair(message: impl Into<String>) -> Self {
+        QuoteError::new(QuoteErrorKind::UnsupportedPair, message)
+    }
+
+    pub fn out_of_limits(message: impl Into<String>) -> Self {
+        QuoteError::new(QuoteErrorKind::AmountOutOfLimits, message)
+    }
+
+    pub fn upstream(message: impl Into<String>) -> Self {
+        QuoteError::new(QuoteErrorKind::UpstreamHttp, message)
+    }
+
+    pub fn gas_estimation(message: impl Into<String>) -> Self {
+        QuoteError::new(QuoteErrorKind::GasEstimationFailed, message)
+    }
+
+    // Prefixes the message while keeping the kind
+    pub fn context(self, context: &str) -> Self {
+        QuoteError { kind: self.kind, message: format!("{}: {}", context, self.message) }
+    }
+}
+
+impl fmt::Display for QuoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<String> for QuoteError {
+    fn from(message: String) -> Self {
+        QuoteError::new(QuoteErrorKind::Other, message)
+    }
+}
+
+impl From<&str> for QuoteError {
+    fn from(message: &str) -> Self {
+        QuoteError::new(QuoteErrorKind::Other, message)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteKind {
+    // Same-chain swaps only
+    Swap,
+    // Cross-chain transfers only
+    Bridge,
+    // Both same-chain and cross-chain
+    Any,
+}
+
+#[derive(Debug, Clone)]
+pub enum ChainSupport {
+    // Chains come from dappConfig's fromChainIds/toChainIds
+    DappConfig,
+    // Chains come from the dapp's bundled paths.json
+    Bundled(Vec<u64>),
+}
+
+#[derive(Debug, Clone)]
+pub struct AdapterCapabilities {
+    pub chains: ChainSupport,
+    pub kind: RouteKind,
+    pub exact_in: bool,
+    pub exact_out: bool,
+    // Signature-based approvals the adapter can encode into its calldata
+    pub permits: Vec<PermitKind>,
+}
+
+impl AdapterCapabilities {
+    pub fn new(kind: RouteKind) -> Self {
+        AdapterCapabilities {
+            chains: ChainSupport::DappConfig,
+            kind,
+            exact_in: true,
+            exact_out: false,
+            permits: Vec::new(),
+        }
+    }
+
+    pub fn with_bundled_chains(mut self, chain_ids: Vec<u64>) -> Self {
+        self.chains = ChainSupport::Bundled(chain_ids);
+        self
+    }
+
+    pub fn with_exact_out(mut self) -> Self {
+        self.exact_out = true;
+        self
+    }
+
+    pub fn with_permits(mut self, permits: Vec<PermitKind>) -> Self {
+        self.permits = permits;
+        self
+    }
+
+    pub fn bundled_chain_ids(&self) -> Option<&Vec<u64>> {
+        match &self.chains {
+            ChainSupport::Bundled(chain_ids) => Some(chain_ids),
+            ChainSupport::DappConfig => None,
+        }
+    }
+
+    pub fn supports_route(&self, from_chain_id: u64, to_chain_id: u64) -> bool {
+        let kind_matches = match self.kind {
+            RouteKind::Swap => from_chain_id == to_chain_id,
+            RouteKind::Bridge => from_chain_id != to_chain_id,
+            RouteKind::Any => true,
+        };
+        let chains_match = match &self.chains {
+            ChainSupport::Bundled(chain_ids) => chain_ids.contains(&from_chain_id) && chain_ids.contains(&to_chain_id),
+            ChainSupport::DappConfig => true,
+        };
+        kind_matches && chains_match
+    }
+
+    pub fn supports_mode(&self, exact_out: bool) -> bool {
+        if exact_out { self.exact_out } else { self.exact_in }
+    }
+}
+
+pub trait DappAdapter: Send + Sync {
+    // Key used in dappConfig, options.dapps and the quote's `tool`
+    fn name(&self) -> &'static str;
+
+    fn capabilities(&self) -> AdapterCapabilities;
+
+    // Contract ABIs whose custom errors explain a reverted simulation
+    fn error_abis(&self) -> Vec<&'static Abi> {
+        Vec::new()
+    }
+
+    // Permit kinds usable for this input token, for adapters that know per-token support
+    fn permits_for(&self, _chain_id: u64, _token_address: &str) -> Vec<PermitKind> {
+        self.capabilities().permits
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>>;
+}
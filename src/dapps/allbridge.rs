@@ -0,0 +1,269 @@
+use ethers::prelude::*;
+use ethers::abi::Token;
+use ethers::utils::hex;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::str::FromStr;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use rand::Rng;
+use tracing::{debug, error};
+use crate::utils::utils::get_random_rpc_proxy_provider;
+use crate::utils::format_swap_details::format_swap_details;
+use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
+
+// Pools keep balances with 3 decimals regardless of the token decimals
+const SYSTEM_PRECISION: u32 = 3;
+// Messenger protocol id for the Allbridge messenger
+const ALLBRIDGE_MESSENGER: u8 = 1;
+
+lazy_static::lazy_static! {
+    static ref TOKENS: Value = {
+        let tokens_str = include_str!("./abi/allbridge/tokens.json");
+        serde_json::from_str(tokens_str).expect("Failed to parse tokens.json")
+    };
+    static ref POOL_ABI: ethers::abi::Abi = serde_json::from_str(r#"[
+        {"inputs":[],"name":"a","outputs":[{"internalType":"uint256","name":"","type":"uint256"}],"stateMutability":"view","type":"function"},
+        {"inputs":[],"name":"d","outputs":[{"internalType":"uint256","name":"","type":"uint256"}],"stateMutability":"view","type":"function"},
+        {"inputs":[],"name":"tokenBalance","outputs":[{"internalType":"uint256","name":"","type":"uint256"}],"stateMutability":"view","type":"function"},
+        {"inputs":[],"name":"vUsdBalance","outputs":[{"internalType":"uint256","name":"","type":"uint256"}],"stateMutability":"view","type":"function"},
+        {"inputs":[],"name":"feeShareBP","outputs":[{"internalType":"uint256","name":"","type":"uint256"}],"stateMutability":"view","type":"function"}
+    ]"#).expect("Failed to parse pool ABI");
+    static ref BRIDGE_ABI: ethers::abi::Abi = serde_json::from_str(r#"[
+        {"inputs":[{"internalType":"bytes32","name":"token","type":"bytes32"},{"internalType":"uint256","name":"amount","type":"uint256"},{"internalType":"bytes32","name":"recipient","type":"bytes32"},{"internalType":"uint256","name":"destinationChainId","type":"uint256"},{"internalType":"bytes32","name":"receiveToken","type":"bytes32"},{"internalType":"uint256","name":"nonce","type":"uint256"},{"internalType":"enum MessengerProtocol","name":"messenger","type":"uint8"},{"internalType":"uint256","name":"feeTokenAmount","type":"uint256"}],"name":"swapAndBridge","outputs":[],"stateMutability":"payable","type":"function"}
+    ]"#).expect("Failed to parse bridge ABI");
+}
+
+struct PoolState {
+    a: U256,
+    d: U256,
+    token_balance: U256,
+    vusd_balance: U256,
+    fee_share_bp: U256,
+}
+
+fn find_chain(chain_id: u64) -> Option<&'static Value> {
+    TOKENS.as_object()?.values().find(|chain| {
+        chain["chainType"].as_str() == Some("EVM")
+            && chain["chainId"].as_str()
+                .and_then(|id| u64::from_str_radix(id.trim_start_matches("0x"), 16).ok())
+                == Some(chain_id)
+    })
+}
+
+fn find_token<'a>(chain: &'a Value, token_address: &str) -> Option<&'a Value> {
+    chain["tokens"].as_array()?.iter().find(|token| {
+        token["tokenAddress"].as_str().map(|a| a.to_lowercase()) == Some(token_address.to_lowercase())
+            && token["flags"]["swap"].as_bool().unwrap_or(false)
+    })
+}
+
+fn address_to_bytes32(address: Address) -> Vec<u8> {
+    let mut bytes = vec![0u8; 12];
+    bytes.extend_from_slice(address.as_bytes());
+    bytes
+}
+
+fn to_system_precision(amount: U256, decimals: u32) -> U256 {
+    if decimals > SYSTEM_PRECISION {
+        amount / U256::exp10((decimals - SYSTEM_PRECISION) as usize)
+    } else {
+        amount * U256::exp10((SYSTEM_PRECISION - decimals) as usize)
+    }
+}
+
+fn from_system_precision(amount: U256, decimals: u32) -> U256 {
+    if decimals > SYSTEM_PRECISION {
+        amount * U256::exp10((decimals - SYSTEM_PRECISION) as usize)
+    } else {
+        amount / U256::exp10((SYSTEM_PRECISION - decimals) as usize)
+    }
+}
+
+// Solves the pool invariant 4a(x + y) + d = 4ad + d³ / 4xy for y
+fn get_y(x: U256, a: U256, d: U256) -> Option<U256> {
+    if x.is_zero() {
+        return None;
+    }
+    // c = 4a(d - x) - d, which may be negative
+    let four_a: U256 = a * 4;
+    let (c_abs, c_negative) = if d >= x {
+        let positive = four_a * (d - x);
+        if positive >= d { (positive - d, false) } else { (d - positive, true) }
+    } else {
+        (four_a * (x - d) + d, true)
+    };
+
+    let sqrt_arg = x.checked_mul(x.checked_mul(c_abs.checked_mul(c_abs)?)?.checked_add(four_a.checked_mul(d.checked_pow(U256::from(3))?)?)?)?;
+    let sqrt = sqrt_arg.integer_sqrt();
+    let x_c = x * c_abs;
+    let numerator = if c_negative { sqrt.checked_sub(x_c)? } else { sqrt + x_c };
+
+    Some(numerator / (four_a * x * 2))
+}
+
+// Source side: token -> vUSD
+fn swap_to_vusd(amount: U256, decimals: u32, pool: &PoolState) -> Option<U256> {
+    let fee = amount * pool.fee_share_bp / U256::from(10_000u64);
+    let amount_without_fee = amount - fee;
+    let new_token_balance = pool.token_balance + to_system_precision(amount_without_fee, decimals);
+    let new_vusd_balance = get_y(new_token_balance, pool.a, pool.d)?;
+    pool.vusd_balance.checked_sub(new_vusd_balance)
+}
+
+// Destination side: vUSD -> token
+fn swap_from_vusd(vusd_amount: U256, decimals: u32, pool: &PoolState) -> Option<U256> {
+    let new_vusd_balance = pool.vusd_balance + vusd_amount;
+    let new_token_balance = get_y(new_vusd_balance, pool.a, pool.d)?;
+    let result = from_system_precision(pool.token_balance.checked_sub(new_token_balance)?, decimals);
+    let fee = result * pool.fee_share_bp / U256::from(10_000u64);
+    Some(result - fee)
+}
+
+async fn fetch_pool_state(chain_id: u64, pool_address: &str, state: &Arc<AppState>) -> Result<PoolState, String> {
+    let provider = get_random_rpc_proxy_provider(chain_id, &state.rpc_proxy_providers)
+        .ok_or_else(|| "No RPC provider available".to_string())?;
+    let pool = Contract::new(Address::from_str(pool_address).map_err(|e| e.to_string())?, POOL_ABI.clone(), provider);
+
+    let read = |name: &'static str| {
+        let pool = pool.clone();
+        async move {
+            pool.method::<_, U256>(name, ())
+                .map_err(|e| e.to_string())?
+                .call()
+                .await
+                .map_err(|e| format!("Failed to read pool {}: {}", name, e))
+        }
+    };
+
+    let (a, d, token_balance, vusd_balance, fee_share_bp) = futures::try_join!(
+        read("a"),
+        read("d"),
+        read("tokenBalance"),
+        read("vUsdBalance"),
+        read("feeShareBP")
+    )?;
+
+    Ok(PoolState { a, d, token_balance, vusd_balance, fee_share_bp })
+}
+
+pub struct Allbridge;
+
+impl DappAdapter for Allbridge {
+    fn name(&self) -> &'static str {
+        "allbridge"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Bridge)
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+    async move {
+        let params = &request.params;
+        debug!("Starting get_swap_quote with params: {:?}", params);
+
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let from_address = request.from_address.as_str();
+        let quote_only = request.quote_only;
+
+        if from_chain_id == to_chain_id {
+            return Err(QuoteError::unsupported_pair("Allbridge only supports cross-chain transfers"));
+        }
+
+        let from_chain = find_chain(from_chain_id)
+            .ok_or_else(|| QuoteError::unsupported_pair(format!("Chain {} is not supported by Allbridge", from_chain_id)))?;
+        let to_chain = find_chain(to_chain_id)
+            .ok_or_else(|| QuoteError::unsupported_pair(format!("Chain {} is not supported by Allbridge", to_chain_id)))?;
+
+        let from_token = find_token(from_chain, from_token_address).ok_or_else(|| QuoteError::unsupported_pair("fromToken is not supported by Allbridge"))?;
+        let to_token = find_token(to_chain, to_token_address).ok_or_else(|| QuoteError::unsupported_pair("toToken is not supported by Allbridge"))?;
+
+        let bridge_address = from_chain["bridgeAddress"].as_str().ok_or("Missing bridgeAddress")?;
+        let from_pool_address = from_token["poolAddress"].as_str().ok_or("Missing source poolAddress")?;
+        let to_pool_address = to_token["poolAddress"].as_str().ok_or("Missing destination poolAddress")?;
+        let from_decimals = from_token["decimals"].as_u64().unwrap_or(18) as u32;
+        let to_decimals = to_token["decimals"].as_u64().unwrap_or(18) as u32;
+        let destination_allbridge_id = to_chain["allbridgeChainId"].as_u64().ok_or("Missing allbridgeChainId")?;
+        let confirmations = from_chain["confirmations"].as_u64().unwrap_or(0);
+        let relayer_fee = U256::from_dec_str(from_chain["txCostAmount"]["transfer"].as_str().unwrap_or("0"))
+            .map_err(|e| format!("Invalid txCostAmount: {}", e))?;
+
+        let amount_in = U256::from_dec_str(amount).map_err(|e| format!("Invalid amount: {}", e))?;
+
+        let (from_pool, to_pool) = futures::try_join!(
+            fetch_pool_state(from_chain_id, from_pool_address, &state),
+            fetch_pool_state(to_chain_id, to_pool_address, &state)
+        ).map_err(QuoteError::upstream)?;
+
+        let vusd_amount = swap_to_vusd(amount_in, from_decimals, &from_pool)
+            .ok_or("Insufficient liquidity in source pool")?;
+        let amount_out = swap_from_vusd(vusd_amount, to_decimals, &to_pool)
+            .ok_or("Insufficient liquidity in destination pool")?;
+
+        if amount_out.is_zero() {
+            return Err(QuoteError::out_of_limits("Amount is too small to bridge with Allbridge"));
+        }
+
+        debug!("Allbridge quote: vUsd={}, amountOut={}, relayerFee={}", vusd_amount, amount_out, relayer_fee);
+
+        let token = Address::from_str(from_token_address).map_err(|e| e.to_string())?;
+        let recipient = Address::from_str(to_address).map_err(|e| format!("Invalid toAddress: {}", e))?;
+        let receive_token = Address::from_str(to_token_address).map_err(|e| e.to_string())?;
+        let nonce = U256::from_big_endian(&rand::thread_rng().gen::<[u8; 32]>());
+
+        let function = BRIDGE_ABI.function("swapAndBridge")
+            .map_err(|e| format!("Failed to get function swapAndBridge: {:?}", e))?;
+        let encoded = function.encode_input(&[
+            Token::FixedBytes(address_to_bytes32(token)),
+            Token::Uint(amount_in),
+            Token::FixedBytes(address_to_bytes32(recipient)),
+            Token::Uint(U256::from(destination_allbridge_id)),
+            Token::FixedBytes(address_to_bytes32(receive_token)),
+            Token::Uint(nonce),
+            Token::Uint(U256::from(ALLBRIDGE_MESSENGER)),
+            Token::Uint(U256::zero()), // Relayer fee is paid in the native token
+        ]).map_err(|e| format!("Failed to encode swapAndBridge: {}", e))?;
+
+        let transaction_data = json!({
+            "from": from_address,
+            "to": bridge_address,
+            "chainID": from_chain_id,
+            "data": format!("0x{}", hex::encode(encoded)),
+            "value": relayer_fee.to_string(),
+        });
+
+        let relayer_fee_value = Value::String(relayer_fee.to_string());
+        let dapp_options = json!({ "confirmations": confirmations });
+
+        let formatted_result = format_swap_details(
+            "allbridge",
+            params,
+            &transaction_data,
+            &Value::String(amount_out.to_string()),
+            &Value::String(bridge_address.to_string()),
+            &params["gasPrices"],
+            if quote_only { Some("quote") } else { None },
+            Some(&relayer_fee_value),
+            Some(&dapp_options),
+            &state
+        ).await;
+
+        if let Err(e) = &formatted_result {
+            error!("Error formatting swap details: {}", e);
+        }
+
+        formatted_result.map_err(|e| e.context("Error formatting swap details"))
+    }.boxed()
+}
@@ -1,31 +1,67 @@
 use serde_json::{json, Value};
 use std::sync::Arc;
 use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
 use crate::utils::utils::{get_random_proxy_client, get_replaced_addresses};
-use crate::utils::format_swap_details::format_swap_details;
+use crate::utils::format_swap_details::{format_swap_details, max_amount_in};
+use ethers::types::U256;
 use futures::future::BoxFuture;
 use futures::FutureExt;
 use tracing::{debug, error};
 
-pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static, Result<Value, String>> {
-    async move {
+// One order for the Balancer order API; `amount` is the sell amount, or the buy amount for "buy" orders
+struct BalancerOrder<'a> {
+    chain_id: u64,
+    sell_token: &'a str,
+    buy_token: &'a str,
+    kind: &'a str,
+    amount: &'a str,
+    sender: &'a str,
+    receiver: &'a str,
+    slippage_percentage: f64,
+    gas_price_wei: &'a str,
+}
+
+pub struct Balancer;
+
+impl DappAdapter for Balancer {
+    fn name(&self) -> &'static str {
+        "balancer"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Swap).with_exact_out()
+    }
 
-        let from_chain_id = params["fromChainId"].as_u64().ok_or("Invalid fromChainId")?;
-        let to_chain_id = params["toChainId"].as_u64().unwrap_or(from_chain_id);
-        let amount = params["amount"].as_str().ok_or("Invalid amount")?;
-        let from_token_address = params["fromTokenAddress"].as_str().ok_or("Invalid fromTokenAddress")?;
-        let to_token_address = params["toTokenAddress"].as_str().ok_or("Invalid toTokenAddress")?;
-        let to_address = params["toAddress"].as_str().ok_or("Invalid toAddress")?;
-        let from_address = params["fromAddress"].as_str().ok_or("Invalid fromAddress")?;
-        let options = params["options"].as_object().ok_or("Invalid options")?;
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+    async move {
+        let params = &request.params;
+
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let from_address = request.from_address.as_str();
         let gas_prices = params["gasPrices"].as_array().ok_or("Invalid gasPrices")?;
         let gas_price_wei = gas_prices.get(0)
             .and_then(|v| v.as_str())
             .ok_or("Invalid gasPriceGwei")?;
-        let quote_only = params["quoteOnly"].as_bool().unwrap_or(false);
+        let quote_only = request.quote_only;
         
-        let slippage_percentage = options["slippage"].as_str().and_then(|s| s.parse::<f64>().ok()).or_else(|| options["slippage"].as_f64()).ok_or_else(|| "Invalid slippage".to_string())?;
+        let slippage_percentage = request.slippage;
 
+        // Buy orders fix the output and let Balancer quote the input
+        let (order_kind, order_amount) = match request.to_amount.as_deref() {
+            Some(to_amount) => ("buy", to_amount),
+            None => ("sell", amount),
+        };
 
         // Replace token addresses
         let (valid_from_token, valid_to_token) = get_replaced_addresses(
@@ -43,32 +79,44 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
         debug!("Replaced addresses: from={}, to={}", valid_from_token, valid_to_token);
 
         // Fetch the quote
-        let quote_result = get_quote(
-            from_chain_id,
-            &valid_from_token,
-            &valid_to_token,
-            amount,
-            from_address,
-            to_address,
+        let order = BalancerOrder {
+            chain_id: from_chain_id,
+            sell_token: &valid_from_token,
+            buy_token: &valid_to_token,
+            kind: order_kind,
+            amount: order_amount,
+            sender: from_address,
+            receiver: to_address,
             slippage_percentage,
             gas_price_wei,
-            &state
-        ).await.map_err(|e| {
+        };
+        let quote_result = get_quote(&order, &state).await.map_err(|e| {
             error!("Failed to retrieve quote: {}", e);
-            e.to_string()
+            QuoteError::upstream(e)
         })?;
 
         if quote_result.get("error").is_some() {
             error!("Balancer API error: {:?}", quote_result);
-            return Err("Failed to retrieve a valid quote from Balancer.".to_string());
+            return Err(QuoteError::upstream("Failed to retrieve a valid quote from Balancer."));
         }
 
+        // Extract sellAmount, the input Balancer quoted for a buy order
+        let sell_amount_hex = quote_result["price"]["sellAmount"]["hex"]
+            .as_str()
+            .ok_or("Invalid sellAmount hex")?;
+        let sell_amount = U256::from_str_radix(&sell_amount_hex[2..], 16)
+            .map_err(|_| "Failed to parse sellAmount from hex")?;
+
         let zero_address = "0x0000000000000000000000000000000000000000";
-        let value = if valid_from_token == zero_address {
-            amount.to_string()
-        } else {
+        let value = if valid_from_token != zero_address {
             "0".to_string()
+        } else if request.is_exact_out() {
+            // The native input has to cover the worst case the order may pull
+            max_amount_in(sell_amount, slippage_percentage).to_string()
+        } else {
+            amount.to_string()
         };
+        let dapp_options = request.is_exact_out().then(|| json!({ "fromAmount": sell_amount.to_string() }));
 
         // Extract and convert buyAmount (BigNumber to string)
         let buy_amount_hex = quote_result["price"]["buyAmount"]["hex"]
@@ -100,19 +148,19 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
         debug!("gasPrices: {:?}", params["gasPrices"]);
         debug!("gasEstimate: None");
         debug!("additional_fee: None");
-        debug!("dapp_options: None");
+        debug!("dapp_options: {:?}", dapp_options);
 
         let formatted_data = if quote_only {
             format_swap_details(
                 "balancer",
-                &params,
+                params,
                 &transaction_data,
                 &json!(buy_amount_str), // Pass buyAmount
                 &quote_result["price"]["allowanceTarget"],
                 &params["gasPrices"],
                 Some("0"), 
                 None,
-                None,
+                dapp_options.as_ref(),
                 &state
             ).await.map_err(|e| {
                 error!("Error formatting quote-only swap details: {}", e);
@@ -121,14 +169,14 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
         } else {
             format_swap_details(
                 "balancer",
-                &params,
+                params,
                 &transaction_data,
                 &json!(buy_amount_str), // Pass buyAmount
                 &quote_result["price"]["allowanceTarget"],
                 &params["gasPrices"],
                 None,
                 None,
-                None,
+                dapp_options.as_ref(),
                 &state
             ).await.map_err(|e| {
                 error!("Error formatting swap details: {}", e);
@@ -142,34 +190,24 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
 }
 
 
-async fn get_quote(
-    from_chain_id: u64,
-    from_token_address: &String,
-    to_token_address: &String,
-    amount: &str,
-    from_address: &str,
-    to_address: &str,
-    slippage_percentage: f64,
-    gas_price_wei: &str,
-    state: &Arc<AppState>,
-) -> Result<Value, String> {
+async fn get_quote(order: &BalancerOrder<'_>, state: &Arc<AppState>) -> Result<Value, String> {
     let client = get_random_proxy_client(&state.proxy_clients)
         .ok_or("No proxy client available")?;
 
     let params = json!({
-        "sellToken": from_token_address,
-        "buyToken": to_token_address,
-        "orderKind": "sell",
-        "amount": amount,
-        "gasPrice": gas_price_wei,
-        "sender": from_address,
-        "receiver": to_address,
-        "slippagePercentage": slippage_percentage / 100.0,
+        "sellToken": order.sell_token,
+        "buyToken": order.buy_token,
+        "orderKind": order.kind,
+        "amount": order.amount,
+        "gasPrice": order.gas_price_wei,
+        "sender": order.sender,
+        "receiver": order.receiver,
+        "slippagePercentage": order.slippage_percentage / 100.0,
     });
 
     debug!("Sending request to Balancer API with params: {:?}", params);
 
-    let response = client.post(&format!("https://api.balancer.fi/order/{}", from_chain_id))
+    let response = client.post(&format!("https://api.balancer.fi/order/{}", order.chain_id))
         .json(&params)
         .send()
         .await
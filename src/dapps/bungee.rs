@@ -1,6 +1,7 @@
 use serde_json::{Value, json};
 use std::sync::Arc;
 use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
 use crate::utils::utils::{get_random_proxy_client, get_replaced_addresses};
 use crate::utils::format_swap_details::format_swap_details;
 use futures::future::BoxFuture;
@@ -15,21 +16,37 @@ fn log_error(context: &str, error_msg: &str) -> String {
     full_error_msg
 }
 
-pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static, Result<Value, String>> {
+pub struct Bungee;
+
+impl DappAdapter for Bungee {
+    fn name(&self) -> &'static str {
+        "bungee"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Any)
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
     async move {
+        let params = &request.params;
         debug!("Swap quote parameters: {:?}", params);
 
         // Extract necessary parameters with error handling
-        let from_chain_id = params["fromChainId"].as_u64().ok_or("Invalid or missing fromChainId")?;
-        let to_chain_id = params["toChainId"].as_u64().unwrap_or(from_chain_id);
-        let amount = params["amount"].as_str().ok_or("Invalid or missing amount")?;
-        let from_token_address = params["fromTokenAddress"].as_str().ok_or("Invalid or missing fromTokenAddress")?;
-        let to_token_address = params["toTokenAddress"].as_str().ok_or("Invalid or missing toTokenAddress")?;
-        let from_address = params["fromAddress"].as_str().ok_or("Invalid or missing fromAddress")?;
-        let options = params["options"].as_object().ok_or("Invalid or missing options")?;
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let from_address = request.from_address.as_str();
         let gas_prices = params["gasPrices"].as_array().ok_or("Invalid or missing gasPrices")?;
-        let quote_only = params["quoteOnly"].as_bool().unwrap_or(false);
-        let slippage_tolerance = options["slippage"].as_str().and_then(|s| s.parse::<f64>().ok()).or_else(|| options["slippage"].as_f64()).ok_or_else(|| "Invalid slippage".to_string())?;
+        let quote_only = request.quote_only;
+        let slippage_tolerance = request.slippage;
 
 
         // Replace token addresses
@@ -56,12 +73,12 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
             from_address,
             from_address,  // Use from_address as to_address
             &state
-        ).await.map_err(|e| log_error("Failed to fetch quote", &e))?;
+        ).await.map_err(|e| QuoteError::upstream(log_error("Failed to fetch quote", &e)))?;
 
         debug!("Fetched quote: {:?}", quote);
 
         // Build the transaction
-        let transaction = build_transaction(quote.clone(), &state).await.map_err(|e| log_error("Failed to build transaction", &e))?;
+        let transaction = build_transaction(quote.clone(), &state).await.map_err(|e| QuoteError::upstream(log_error("Failed to build transaction", &e)))?;
 
         debug!("Built transaction: {:?}", transaction);
 
@@ -71,7 +88,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
             .ok_or_else(|| log_error("Missing toAmount in quote", "toAmount is missing or not a valid string"))?;
         
         if to_amount.is_empty() {
-            return Err(log_error("toAmount Error", "toAmount is missing or empty in the quote response"));
+            return Err(log_error("toAmount Error", "toAmount is missing or empty in the quote response").into());
         }
 
         // Extract transaction details
@@ -84,7 +101,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
             .map(|v| v.to_string())
             .map_err(|e| format!("Failed to convert value to wei: {}", e))?;
 
-        let approval_address = transaction["approvalData"]
+        let approval_address = transaction["approvalData"]["allowanceTarget"]
             .as_str()
             .unwrap_or("0x0000000000000000000000000000000000000000");
         
@@ -126,7 +143,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
         let formatted_data = if quote_only {
             format_swap_details(
                 "bungee", 
-                &params, 
+                params, 
                 &quote_data, 
                 &Value::String(to_amount.to_string()), 
                 &Value::String(approval_address.to_string()), 
@@ -139,7 +156,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
         } else {
             format_swap_details(
                 "bungee", 
-                &params, 
+                params, 
                 &quote_data, 
                 &Value::String(to_amount.to_string()), 
                 &Value::String(approval_address.to_string()), 
@@ -149,7 +166,10 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
                 None, 
                 &state
             ).await
-        }.map_err(|e| log_error("Error formatting swap details", &e))?;
+        }.map_err(|e| {
+            log_error("Error formatting swap details", &e.message);
+            e.context("Error formatting swap details")
+        })?;
 
         debug!("Formatted swap details: {:?}", formatted_data);
 
@@ -1,6 +1,7 @@
 use serde_json::{Value, json};
 use std::sync::Arc;
 use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
 use crate::utils::utils::{get_random_proxy_client, get_replaced_addresses};
 use crate::utils::format_swap_details::format_swap_details;
 use futures::future::BoxFuture;
@@ -8,22 +9,38 @@ use futures::FutureExt;
 use tracing::{error, debug};
 use reqwest::Url;
 
-pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static, Result<Value, String>> {
+pub struct Debridge;
+
+impl DappAdapter for Debridge {
+    fn name(&self) -> &'static str {
+        "debridge"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Bridge)
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
     async move {
+        let params = &request.params;
         debug!("Swap quote parameters: {:?}", params);
 
         // Extract necessary parameters with error handling
-        let from_chain_id = params["fromChainId"].as_u64().ok_or("Invalid or missing fromChainId")?;
-        let to_chain_id = params["toChainId"].as_u64().unwrap_or(from_chain_id);
-        let amount = params["amount"].as_str().ok_or("Invalid or missing amount")?;
-        let from_token_address = params["fromTokenAddress"].as_str().ok_or("Invalid or missing fromTokenAddress")?;
-        let to_token_address = params["toTokenAddress"].as_str().ok_or("Invalid or missing toTokenAddress")?;
-        let to_address = params["toAddress"].as_str().ok_or("Invalid or missing toAddress")?;
-        let from_address = params["fromAddress"].as_str().ok_or("Invalid or missing fromAddress")?;
-        let options = params["options"].as_object().ok_or("Invalid or missing options")?;
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let from_address = request.from_address.as_str();
         let gas_prices = params["gasPrices"].as_array().ok_or("Invalid or missing gasPrices")?;
-        let quote_only = params["quoteOnly"].as_bool().unwrap_or(false);
-        let slippage_tolerance = options["slippage"].as_str().and_then(|s| s.parse::<f64>().ok()).or_else(|| options["slippage"].as_f64()).ok_or_else(|| "Invalid slippage".to_string())?;
+        let quote_only = request.quote_only;
+        let slippage_tolerance = request.slippage;
 
 
         // Replace token addresses
@@ -50,7 +67,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
             to_address,
             slippage_tolerance,
             &state
-        ).await.map_err(|e| format!("Failed to fetch a valid quote: {}", e))?;
+        ).await.map_err(|e| QuoteError::upstream(format!("Failed to fetch a valid quote: {}", e)))?;
 
         // Prepare quoteData from the quote response
         let quote_data = json!({
@@ -70,13 +87,15 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
             Some(value) => value,
             None => {
                 error!("Missing receive value in quote");
-                return Ok(json!({
-                    "success": false,
-                    "message": "Missing receive value in quote"
-                }));
+                return Err("Missing receive value in quote".into());
             }
         };
 
+        // With operating expenses prepended the order takes more than the requested amount
+        let from_amount = quote["estimation"]["srcChainTokenIn"]["amount"].as_str()
+            .ok_or("Missing srcChainTokenIn amount in quote")?;
+        let dapp_options = json!({ "fromAmount": from_amount });
+
         let fix_fee = quote["fixFee"].as_str().unwrap_or("0");
 
         // Convert fix_fee to a Value
@@ -101,30 +120,30 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
         let formatted_data = if quote_only {
             format_swap_details(
                 "debridge", 
-                &params, 
+                params, 
                 &quote_data, 
                 &Value::String(receive_value.to_string()), 
                 &Value::String(approval_target.to_string()), 
                 &Value::Array(gas_prices.to_vec()),
                 Some("quote"), 
                 Some(&fix_fee_value), 
-                None, 
+                Some(&dapp_options), 
                 &state
             ).await
         } else {
             format_swap_details(
                 "debridge", 
-                &params, 
+                params, 
                 &quote_data, 
                 &Value::String(receive_value.to_string()), 
                 &Value::String(approval_target.to_string()), 
                 &Value::Array(gas_prices.to_vec()),
                 None, 
                 Some(&fix_fee_value), 
-                None, 
+                Some(&dapp_options), 
                 &state
             ).await
-        }.map_err(|e| format!("Error formatting swap details: {}", e))?;
+        }.map_err(|e| e.context("Error formatting swap details"))?;
 
         debug!("Formatted swap details: {:?}", formatted_data);
 
@@ -159,6 +178,7 @@ async fn get_quote(
         ("dstChainTokenOutRecipient", to_address.to_string()),
         ("srcChainOrderAuthorityAddress", from_address.to_string()),
         ("dstChainOrderAuthorityAddress", from_address.to_string()),
+        // The order pulls the expenses on top of the amount, reported back as srcChainTokenIn
         ("prependOperatingExpense", "true".to_string()),
         ("slippage", slippage.to_string()),
         ("referralCode", state.settings["debridge"]["referralCode"].as_str().unwrap_or("").to_string()),
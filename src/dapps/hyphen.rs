@@ -0,0 +1,318 @@
+use ethers::prelude::*;
+use ethers::abi::Token;
+use ethers::utils::hex;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::str::FromStr;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tracing::{debug, error};
+use crate::utils::utils::get_random_rpc_proxy_provider;
+use crate::utils::gas_cache::get_fee_estimate;
+use crate::utils::fetch_token_details::fetch_token_details;
+use crate::utils::format_swap_details::format_swap_details;
+use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, PermitKind, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
+
+// Hyphen expresses transfer fee percentages with 10 decimals of precision
+const BASE_DIVISOR: u64 = 10_000_000_000;
+
+lazy_static::lazy_static! {
+    static ref LIQUIDITY_POOL_ABI: ethers::abi::Abi = {
+        let abi_str = include_str!("./abi/hyphen/abi.json");
+        serde_json::from_str(abi_str).expect("Failed to parse ABI")
+    };
+    static ref CHAINS: Vec<Value> = {
+        let chains_str = include_str!("./abi/hyphen/chains.json");
+        let chains: Value = serde_json::from_str(chains_str).expect("Failed to parse chains.json");
+        chains["message"].as_array().expect("Invalid chains in chains.json").clone()
+    };
+    static ref TOKENS: Vec<Value> = {
+        let tokens_str = include_str!("./abi/hyphen/tokens.json");
+        let tokens: Value = serde_json::from_str(tokens_str).expect("Failed to parse tokens.json");
+        tokens["message"].as_array().expect("Invalid tokens in tokens.json").clone()
+    };
+}
+
+// Hyphen marks native tokens with the 0xeee address
+fn normalize_token_address(token_address: &str) -> String {
+    let zero_address = "0x0000000000000000000000000000000000000000";
+    let eee_address = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+    if token_address.to_lowercase() == zero_address {
+        eee_address.to_string()
+    } else {
+        token_address.to_lowercase()
+    }
+}
+
+fn get_liquidity_pool(chain_id: u64) -> Option<String> {
+    CHAINS.iter()
+        .find(|chain| chain["chainId"].as_u64() == Some(chain_id) && chain["enabled"].as_bool().unwrap_or(false))
+        .and_then(|chain| chain["contracts"]["hyphen"]["liquidityPool"].as_str())
+        .map(|pool| pool.to_string())
+}
+
+// Finds the per-chain token configs for an asset present on both chains
+fn find_token_pair(from_chain_id: u64, to_chain_id: u64, from_token_address: &str) -> Option<(&'static Value, &'static Value)> {
+    let normalized = normalize_token_address(from_token_address);
+    TOKENS.iter().find_map(|asset| {
+        let from_token = asset.get(from_chain_id.to_string())?;
+        let to_token = asset.get(to_chain_id.to_string())?;
+        if from_token["address"].as_str()?.to_lowercase() == normalized {
+            Some((from_token, to_token))
+        } else {
+            None
+        }
+    })
+}
+
+fn is_bridge_supported(token: &Value) -> bool {
+    token["isSupported"].as_bool().unwrap_or(false) && token["isSupportedOnBridge"].as_bool().unwrap_or(false)
+}
+
+// Hyphen's token list records which tokens implement EIP-2612 permit
+fn supports_eip2612(chain_id: u64, token_address: &str) -> bool {
+    let normalized = normalize_token_address(token_address);
+    TOKENS.iter()
+        .filter_map(|asset| asset.get(chain_id.to_string()))
+        .find(|token| token["address"].as_str().map(|address| address.to_lowercase()) == Some(normalized.clone()))
+        .and_then(|token| token["metaTransactionData"]["supportsEip2612"].as_bool())
+        .unwrap_or(false)
+}
+
+fn scale_decimals(amount: U256, from_decimals: u32, to_decimals: u32) -> U256 {
+    if to_decimals >= from_decimals {
+        amount * U256::exp10((to_decimals - from_decimals) as usize)
+    } else {
+        amount / U256::exp10((from_decimals - to_decimals) as usize)
+    }
+}
+
+// Estimates the executor's destination gas cost, denominated in the destination token
+async fn estimate_executor_fee(
+    to_chain_id: u64,
+    to_token: &Value,
+    base_gas: U256,
+    state: &Arc<AppState>
+) -> Result<U256, String> {
+    let transfer_overhead = U256::from(to_token["transferOverhead"].as_u64().unwrap_or(0));
+    // The cache keeps the destination chain warm, so repeat quotes don't hit its RPC
+    let (fee_estimate, _) = get_fee_estimate(to_chain_id, state).await
+        .map_err(|e| format!("Failed to fetch destination gas price: {}", e))?;
+    let gas_price = fee_estimate.gas_price;
+    let fee_native = (transfer_overhead + base_gas) * gas_price;
+
+    if normalize_token_address(to_token["address"].as_str().unwrap_or_default()) == "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee" {
+        return Ok(fee_native);
+    }
+
+    // Convert the native gas cost into token units using USD prices
+    let to_token_address = to_token["address"].as_str().unwrap_or_default();
+    let token_details = fetch_token_details(
+        vec![("0x0000000000000000000000000000000000000000", to_chain_id), (to_token_address, to_chain_id)],
+        state
+    ).await.map_err(|e| format!("Failed to fetch token details: {}", e))?;
+
+    let native_price = token_details[0].as_ref().and_then(|t| t.price_usd).ok_or("Missing native token price")?;
+    let token_price = token_details[1].as_ref().and_then(|t| t.price_usd).ok_or("Missing token price")?;
+    let token_decimals = to_token["decimal"].as_u64().unwrap_or(18) as i32;
+
+    let fee_usd = fee_native.as_u128() as f64 / 1e18 * native_price;
+    let fee_token = (fee_usd / token_price * 10f64.powi(token_decimals)).ceil() as u128;
+
+    Ok(U256::from(fee_token))
+}
+
+pub struct Hyphen;
+
+impl DappAdapter for Hyphen {
+    fn name(&self) -> &'static str {
+        "hyphen"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Bridge).with_permits(vec![PermitKind::Eip2612])
+    }
+
+    fn permits_for(&self, chain_id: u64, token_address: &str) -> Vec<PermitKind> {
+        if supports_eip2612(chain_id, token_address) {
+            self.capabilities().permits
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+    async move {
+        let params = &request.params;
+        debug!("Starting get_swap_quote with params: {:?}", params);
+
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let from_address = request.from_address.as_str();
+        let quote_only = request.quote_only;
+
+        if from_chain_id == to_chain_id {
+            return Err(QuoteError::unsupported_pair("Hyphen only supports cross-chain transfers"));
+        }
+
+        let from_pool_address = get_liquidity_pool(from_chain_id)
+            .ok_or_else(|| QuoteError::unsupported_pair(format!("Hyphen is not available on chain {}", from_chain_id)))?;
+        let to_pool_address = get_liquidity_pool(to_chain_id)
+            .ok_or_else(|| QuoteError::unsupported_pair(format!("Hyphen is not available on chain {}", to_chain_id)))?;
+
+        let (from_token, to_token) = find_token_pair(from_chain_id, to_chain_id, from_token_address)
+            .ok_or_else(|| QuoteError::unsupported_pair("Token pair is not supported by Hyphen"))?;
+
+        if !is_bridge_supported(from_token) || !is_bridge_supported(to_token) {
+            return Err(QuoteError::unsupported_pair("Token is not supported on the Hyphen bridge"));
+        }
+
+        let to_token_hyphen_address = to_token["address"].as_str().ok_or("Invalid destination token address")?;
+        if normalize_token_address(to_token_address) != to_token_hyphen_address.to_lowercase() {
+            return Err(QuoteError::unsupported_pair("Hyphen cannot deliver the requested toToken"));
+        }
+
+        let from_decimals = from_token["decimal"].as_u64().unwrap_or(18) as u32;
+        let to_decimals = to_token["decimal"].as_u64().unwrap_or(18) as u32;
+
+        let amount_in = U256::from_dec_str(amount).map_err(|e| format!("Invalid amount: {}", e))?;
+        let from_token_parsed = Address::from_str(&normalize_token_address(from_token_address)).map_err(|e| e.to_string())?;
+        let to_token_parsed = Address::from_str(to_token_hyphen_address).map_err(|e| e.to_string())?;
+
+        let from_provider = get_random_rpc_proxy_provider(from_chain_id, &state.rpc_proxy_providers)
+            .ok_or_else(|| QuoteError::upstream("No RPC provider available"))?;
+        let to_provider = get_random_rpc_proxy_provider(to_chain_id, &state.rpc_proxy_providers)
+            .ok_or_else(|| QuoteError::upstream("No RPC provider available"))?;
+
+        let from_pool = Contract::new(Address::from_str(&from_pool_address).map_err(|e| e.to_string())?, LIQUIDITY_POOL_ABI.clone(), from_provider);
+        let to_pool = Contract::new(Address::from_str(&to_pool_address).map_err(|e| e.to_string())?, LIQUIDITY_POOL_ABI.clone(), to_provider);
+
+        // Incentive paid by the source pool when it is short on liquidity
+        let reward_amount: U256 = from_pool.method::<_, U256>("getRewardAmount", (amount_in, from_token_parsed))
+            .map_err(|e| e.to_string())?
+            .call()
+            .await
+            .map_err(|e| QuoteError::upstream(format!("Failed to get reward amount: {}", e)))?;
+
+        // Fees are charged in destination token units on the destination pool
+        let amount_on_destination = scale_decimals(amount_in + reward_amount, from_decimals, to_decimals);
+        let transfer_fee_perc: U256 = to_pool.method::<_, U256>("getTransferFee", (to_token_parsed, amount_on_destination))
+            .map_err(|e| e.to_string())?
+            .call()
+            .await
+            .map_err(|e| QuoteError::upstream(format!("Failed to get transfer fee: {}", e)))?;
+        let base_gas: U256 = to_pool.method::<_, U256>("baseGas", ())
+            .map_err(|e| e.to_string())?
+            .call()
+            .await
+            .map_err(|e| QuoteError::upstream(format!("Failed to get base gas: {}", e)))?;
+
+        let lp_fee = amount_on_destination * transfer_fee_perc / U256::from(BASE_DIVISOR);
+        let executor_fee = estimate_executor_fee(to_chain_id, to_token, base_gas, &state).await
+            .map_err(QuoteError::upstream)?;
+
+        let total_fee = lp_fee + executor_fee;
+        if total_fee >= amount_on_destination {
+            return Err(QuoteError::out_of_limits("Amount is too small to cover Hyphen fees"));
+        }
+        let amount_out = amount_on_destination - total_fee;
+
+        debug!("Hyphen quote: reward={}, lpFee={}, executorFee={}, amountOut={}", reward_amount, lp_fee, executor_fee, amount_out);
+
+        let tag = state.settings["hyphen"]["tag"].as_str().unwrap_or("hyphen");
+        let receiver = Address::from_str(to_address).map_err(|e| format!("Invalid toAddress: {}", e))?;
+        let is_from_eth = normalize_token_address(from_token_address) == "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+
+        let (function_name, function_params): (&str, Vec<Token>) = if is_from_eth {
+            ("depositNative", vec![
+                Token::Address(receiver),
+                Token::Uint(U256::from(to_chain_id)),
+                Token::String(tag.to_string()),
+            ])
+        } else if let Some(permit) = &request.permit {
+            // The pool calls the token's permit for exactly the deposited amount
+            if permit.value != amount_in {
+                return Err("Permit value does not match the Hyphen deposit amount".into());
+            }
+            let (v, r, s) = permit.split();
+            ("permitEIP2612AndDepositErc20", vec![
+                Token::Address(from_token_parsed),
+                Token::Address(receiver),
+                Token::Uint(amount_in),
+                Token::Uint(U256::from(to_chain_id)),
+                Token::Tuple(vec![
+                    Token::Uint(permit.nonce),
+                    Token::Uint(permit.deadline),
+                    Token::Bool(true),
+                    Token::Uint(U256::from(v)),
+                    Token::FixedBytes(r.to_vec()),
+                    Token::FixedBytes(s.to_vec()),
+                ]),
+                Token::String(tag.to_string()),
+            ])
+        } else {
+            ("depositErc20", vec![
+                Token::Uint(U256::from(to_chain_id)),
+                Token::Address(from_token_parsed),
+                Token::Address(receiver),
+                Token::Uint(amount_in),
+                Token::String(tag.to_string()),
+            ])
+        };
+
+        let function = LIQUIDITY_POOL_ABI.function(function_name)
+            .map_err(|e| format!("Failed to get function {}: {:?}", function_name, e))?;
+        let encoded = function.encode_input(&function_params)
+            .map_err(|e| format!("Failed to encode function call: {}. Function: {}, Params: {:?}", e, function_name, function_params))?;
+
+        let transaction_data = json!({
+            "from": from_address,
+            "to": from_pool_address,
+            "chainID": from_chain_id,
+            "data": format!("0x{}", hex::encode(encoded)),
+            "value": if is_from_eth { amount } else { "0" },
+        });
+
+        let formatted_result = format_swap_details(
+            "hyphen",
+            params,
+            &transaction_data,
+            &Value::String(amount_out.to_string()),
+            &Value::String(from_pool_address.clone()),
+            &params["gasPrices"],
+            if quote_only { Some("quote") } else { None },
+            None,
+            None,
+            &state
+        ).await;
+
+        if let Err(e) = &formatted_result {
+            error!("Error formatting swap details: {}", e);
+        }
+
+        formatted_result.map_err(|e| e.context("Error formatting swap details"))
+    }.boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permits_follow_token_list_flag() {
+        // USDT: permit on Polygon, plain approve on Ethereum
+        assert_eq!(Hyphen.permits_for(137, "0xc2132d05d31c914a87c6611c10748aeb04b58e8f"), vec![PermitKind::Eip2612]);
+        assert!(Hyphen.permits_for(1, "0xdac17f958d2ee523a2206206994597c13d831ec7").is_empty());
+        assert!(Hyphen.permits_for(1, "0x0000000000000000000000000000000000000000").is_empty());
+    }
+}
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use std::fs;
 use lazy_static::lazy_static;
 use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
 use crate::utils::utils::get_random_proxy_client;
 use crate::utils::format_swap_details::format_swap_details;
 use std::path::PathBuf;
@@ -27,23 +28,39 @@ lazy_static! {
         .clone();
 }
 
-pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static, Result<Value, String>> {
+pub struct Jumper;
+
+impl DappAdapter for Jumper {
+    fn name(&self) -> &'static str {
+        "jumper"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Any)
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
     async move {
-        let from_chain_id = params["fromChainId"].as_u64().ok_or("Invalid fromChainId")?;
-        let to_chain_id = params["toChainId"].as_u64().unwrap_or(from_chain_id);
-        let amount = params["amount"].as_str().ok_or("Invalid amount")?;
-        let from_token_address = params["fromTokenAddress"].as_str().ok_or("Invalid fromTokenAddress")?;
-        let to_token_address = params["toTokenAddress"].as_str().ok_or("Invalid toTokenAddress")?;
-        let to_address = params["toAddress"].as_str().ok_or("Invalid toAddress")?;
-        let from_address = params["fromAddress"].as_str().ok_or("Invalid fromAddress")?;
-        let options = params["options"].as_object().ok_or("Invalid options")?;
-        let quote_only = params["quoteOnly"].as_bool().unwrap_or(false);
-        let slippage_percentage = options["slippage"].as_str().and_then(|s| s.parse::<f64>().ok()).or_else(|| options["slippage"].as_f64()).map(|v| v / 100.0).ok_or_else(|| "Invalid slippage".to_string())?;
+        let params = &request.params;
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let from_address = request.from_address.as_str();
+        let quote_only = request.quote_only;
+        let slippage_percentage = request.slippage / 100.0;
         let from_chain_exists = CHAINS.iter().any(|chain| chain["id"].as_u64() == Some(from_chain_id));
         let to_chain_exists = CHAINS.iter().any(|chain| chain["id"].as_u64() == Some(to_chain_id));
 
         if !from_chain_exists || !to_chain_exists {
-            return Err("One or both chain IDs are not supported.".to_string());
+            return Err(QuoteError::unsupported_pair("One or both chain IDs are not supported."));
         }
 
         let quote = get_quote(
@@ -56,7 +73,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
             to_address,
             slippage_percentage,
             &state,
-        ).await?;
+        ).await.map_err(QuoteError::upstream)?;
 
         if let Some(mut quote) = quote {
             if let Some(transaction_request) = quote["transactionRequest"].as_object_mut() {
@@ -80,7 +97,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
             let formatted_data = if quote_only {
                 format_swap_details(
                     "jumper",
-                    &params,
+                    params,
                     &quote["transactionRequest"],
                     &quote["estimate"]["toAmount"],
                     &quote["estimate"]["approvalAddress"],
@@ -93,7 +110,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
             } else {
                 format_swap_details(
                     "jumper",
-                    &params,
+                    params,
                     &quote["transactionRequest"],
                     &quote["estimate"]["toAmount"],
                     &quote["estimate"]["approvalAddress"],
@@ -107,7 +124,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
 
             Ok(formatted_data)
         } else {
-            Err("Unable to process quote.".to_string())
+            Err("Unable to process quote.".into())
         }
     }.boxed()
 }
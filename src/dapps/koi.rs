@@ -9,6 +9,7 @@ use tracing::debug;
 use crate::utils::utils::{get_replaced_addresses, get_random_rpc_proxy_provider};
 use crate::utils::format_swap_details::format_swap_details;
 use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
 
 // Constants
 const ROUTER_ADDRESS: &str = "0x8B791913eB07C32779a16750e3868aA8495F5964";
@@ -20,19 +21,35 @@ lazy_static::lazy_static! {
     };
 }
 
-pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static, Result<Value, String>> {
+pub struct Koi;
+
+impl DappAdapter for Koi {
+    fn name(&self) -> &'static str {
+        "koi"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Swap)
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
     async move {
+        let params = &request.params;
         debug!("Starting get_swap_quote with params: {:?}", params);
 
-        let from_chain_id = params["fromChainId"].as_u64().ok_or("Invalid fromChainId")?;
-        let amount = params["amount"].as_str().ok_or("Invalid amount")?;
-        let from_token_address = params["fromTokenAddress"].as_str().ok_or("Invalid fromTokenAddress")?;
-        let to_token_address = params["toTokenAddress"].as_str().ok_or("Invalid toTokenAddress")?;
-        let to_address = params["toAddress"].as_str().ok_or("Invalid toAddress")?;
-        let from_address = params["fromAddress"].as_str().ok_or("Invalid fromAddress")?;
-        let quote_only = params["quoteOnly"].as_bool().unwrap_or(false);
-        let options = params["options"].as_object().ok_or("Invalid options")?;
-        let slippage = options["slippage"].as_str().and_then(|s| s.parse::<f64>().ok()).or_else(|| options["slippage"].as_f64()).ok_or_else(|| "Invalid slippage".to_string())?;
+        let from_chain_id = request.from_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let from_address = request.from_address.as_str();
+        let quote_only = request.quote_only;
+        let slippage = request.slippage;
 
 
         let zero_address = "0x0000000000000000000000000000000000000000";
@@ -42,7 +59,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
         let is_to_eth = [zero_address, eth_marker_address].contains(&to_token_address.to_lowercase().as_str());
 
         let provider = get_random_rpc_proxy_provider(from_chain_id, &state.rpc_proxy_providers)
-            .ok_or_else(|| "No RPC provider available".to_string())?;
+            .ok_or_else(|| QuoteError::upstream("No RPC provider available"))?;
         let router_contract = Contract::new(Address::from_str(ROUTER_ADDRESS).unwrap(), ROUTER_ABI.clone(), provider.clone());
 
         let replaced_addresses = get_replaced_addresses(from_token_address, to_token_address, from_chain_id, from_chain_id, "koi", &state)
@@ -60,7 +77,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
         )).map_err(|e| e.to_string())?
         .call()
         .await
-        .map_err(|e| format!("Failed to get reserves: {}", e))?;
+        .map_err(|e| QuoteError::upstream(format!("Failed to get reserves: {}", e)))?;
 
         let amount_out: U256 = router_contract.method::<_, U256>("quote", (
             U256::from_dec_str(amount).map_err(|e| e.to_string())?,
@@ -69,7 +86,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
         )).map_err(|e| e.to_string())?
         .call()
         .await
-        .map_err(|e| format!("Failed to get quote: {}", e))?;
+        .map_err(|e| QuoteError::upstream(format!("Failed to get quote: {}", e)))?;
 
         if quote_only {
             let quote_data = json!({
@@ -81,7 +98,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
             });
             format_swap_details(
                 "koi",
-                &params,
+                params,
                 &quote_data,
                 &Value::String(amount_out.to_string()),
                 &Value::String(ROUTER_ADDRESS.to_string()),
@@ -90,7 +107,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
                 None,
                 None,
                 &state
-            ).await.map_err(|e| format!("Failed to format swap details: {}", e))
+            ).await.map_err(|e| e.context("Failed to format swap details"))
         } else {
             let slippage_adjusted = (100.0 - slippage) / 100.0;
             let amount_out_min = (amount_out.as_u128() as f64 * slippage_adjusted).floor() as u128;
@@ -145,7 +162,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
 
             format_swap_details(
                 "koi",
-                &params,
+                params,
                 &tx,
                 &Value::String(amount_out.to_string()),
                 &Value::String(ROUTER_ADDRESS.to_string()),
@@ -154,7 +171,7 @@ pub fn get_swap_quote(params: Value, state: Arc<AppState>) -> BoxFuture<'static,
                 None,
                 None,
                 &state
-            ).await.map_err(|e| format!("Failed to format swap details: {}", e))
+            ).await.map_err(|e| e.context("Failed to format swap details"))
         }
     }.boxed()
 }
\ No newline at end of file
@@ -0,0 +1,213 @@
+use serde_json::{json, Value};
+use std::sync::Arc;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tracing::{debug, error};
+use reqwest::Url;
+use crate::utils::utils::get_random_proxy_client;
+use crate::utils::format_swap_details::format_swap_details;
+use crate::utils::token_conversion::{format_units, parse_units};
+use crate::utils::token_catalogue::{find_network, find_token};
+use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
+
+const API_URL: &str = "https://api.layerswap.io/api/v2";
+
+// The source and destination of a transfer, in Layerswap's network names and symbols
+struct TransferRoute<'a> {
+    source_network: &'a str,
+    source_token: &'a str,
+    destination_network: &'a str,
+    destination_token: &'a str,
+    // Human-readable amount of the source token
+    amount: &'a str,
+}
+
+async fn get_quote(route: &TransferRoute<'_>, state: &Arc<AppState>) -> Result<Value, QuoteError> {
+    let client = get_random_proxy_client(&state.proxy_clients)
+        .ok_or_else(|| QuoteError::upstream("No proxy client available"))?;
+
+    let url = Url::parse(&format!("{}/quote", API_URL)).map_err(|e| e.to_string())?;
+    let params = [
+        ("source_network", route.source_network),
+        ("source_token", route.source_token),
+        ("destination_network", route.destination_network),
+        ("destination_token", route.destination_token),
+        ("amount", route.amount),
+        ("refuel", "false"),
+        ("use_deposit_address", "false"),
+    ];
+
+    let response = client.get(url)
+        .query(&params)
+        .header("X-LS-APIKEY", state.settings["layerswap"]["apiKey"].as_str().unwrap_or(""))
+        .send()
+        .await
+        .map_err(|e| QuoteError::upstream(format!("Failed to fetch quote: {}", e)))?;
+
+    let result = response.json::<Value>().await
+        .map_err(|e| QuoteError::upstream(format!("Failed to parse quote response: {}", e)))?;
+
+    debug!("Layerswap quote response: {:?}", result);
+
+    if !result["error"].is_null() {
+        return Err(QuoteError::upstream(format!("Layerswap API error: {}", result["error"]["message"].as_str().unwrap_or("unknown error"))));
+    }
+
+    Ok(result["data"].clone())
+}
+
+async fn create_swap(
+    route: &TransferRoute<'_>,
+    source_address: &str,
+    destination_address: &str,
+    state: &Arc<AppState>
+) -> Result<Value, QuoteError> {
+    let client = get_random_proxy_client(&state.proxy_clients)
+        .ok_or_else(|| QuoteError::upstream("No proxy client available"))?;
+
+    let body = json!({
+        "source_network": route.source_network,
+        "source_token": route.source_token,
+        "destination_network": route.destination_network,
+        "destination_token": route.destination_token,
+        "amount": route.amount,
+        "source_address": source_address,
+        "destination_address": destination_address,
+        "refuel": false,
+        "use_deposit_address": false,
+    });
+
+    let response = client.post(format!("{}/swaps", API_URL))
+        .json(&body)
+        .header("X-LS-APIKEY", state.settings["layerswap"]["apiKey"].as_str().unwrap_or(""))
+        .send()
+        .await
+        .map_err(|e| QuoteError::upstream(format!("Failed to create swap: {}", e)))?;
+
+    let result = response.json::<Value>().await
+        .map_err(|e| QuoteError::upstream(format!("Failed to parse swap response: {}", e)))?;
+
+    debug!("Layerswap swap response: {:?}", result);
+
+    if !result["error"].is_null() {
+        return Err(QuoteError::upstream(format!("Layerswap API error: {}", result["error"]["message"].as_str().unwrap_or("unknown error"))));
+    }
+
+    Ok(result["data"].clone())
+}
+
+pub struct Layerswap;
+
+impl DappAdapter for Layerswap {
+    fn name(&self) -> &'static str {
+        "layerswap"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Bridge)
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+    async move {
+        let params = &request.params;
+        debug!("Starting get_swap_quote with params: {:?}", params);
+
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let from_address = request.from_address.as_str();
+        let quote_only = request.quote_only;
+
+        let source_network = find_network(from_chain_id)
+            .ok_or_else(|| QuoteError::unsupported_pair(format!("Chain {} is not supported by Layerswap", from_chain_id)))?;
+        let destination_network = find_network(to_chain_id)
+            .ok_or_else(|| QuoteError::unsupported_pair(format!("Chain {} is not supported by Layerswap", to_chain_id)))?;
+
+        let source_token = find_token(source_network, from_token_address).ok_or_else(|| QuoteError::unsupported_pair("fromToken is not supported by Layerswap"))?;
+        let destination_token = find_token(destination_network, to_token_address).ok_or_else(|| QuoteError::unsupported_pair("toToken is not supported by Layerswap"))?;
+
+        let source_network_name = source_network["name"].as_str().ok_or("Invalid source network")?;
+        let destination_network_name = destination_network["name"].as_str().ok_or("Invalid destination network")?;
+        let source_symbol = source_token["symbol"].as_str().ok_or("Invalid source token")?;
+        let destination_symbol = destination_token["symbol"].as_str().ok_or("Invalid destination token")?;
+        let source_decimals = source_token["decimals"].as_u64().unwrap_or(18) as u8;
+        let destination_decimals = destination_token["decimals"].as_u64().unwrap_or(18) as u8;
+
+        // Layerswap takes and returns human-readable amounts
+        let amount_raw = amount.parse::<u128>().map_err(|e| format!("Invalid amount: {}", e))?;
+        let ui_amount = format_units(amount_raw, source_decimals)?;
+
+        let route = TransferRoute {
+            source_network: source_network_name,
+            source_token: source_symbol,
+            destination_network: destination_network_name,
+            destination_token: destination_symbol,
+            amount: &ui_amount,
+        };
+
+        let (receive_amount, transaction_data) = if quote_only {
+            let quote = get_quote(&route, &state).await?;
+
+            let receive_amount = quote["quote"]["receive_amount"].as_f64().ok_or("Missing receive_amount in quote")?;
+
+            (receive_amount, json!({
+                "from": from_address,
+                "to": "quote",
+                "chainID": from_chain_id,
+                "data": "quote",
+                "value": "quote",
+            }))
+        } else {
+            let swap = create_swap(&route, from_address, to_address, &state).await?;
+
+            let receive_amount = swap["quote"]["receive_amount"].as_f64().ok_or("Missing receive_amount in swap")?;
+            let deposit_action = swap["deposit_actions"].as_array()
+                .and_then(|actions| actions.first())
+                .ok_or("Missing deposit action in swap")?;
+
+            let value = if source_token["contract"].is_null() {
+                deposit_action["amount_in_base_units"].as_str().unwrap_or(amount).to_string()
+            } else {
+                "0".to_string()
+            };
+
+            (receive_amount, json!({
+                "from": from_address,
+                "to": deposit_action["to_address"],
+                "chainID": from_chain_id,
+                "data": deposit_action["call_data"].as_str().unwrap_or("0x"),
+                "value": value,
+            }))
+        };
+
+        let to_amount = parse_units(&receive_amount.to_string(), destination_decimals)?;
+
+        let formatted_result = format_swap_details(
+            "layerswap",
+            params,
+            &transaction_data,
+            &Value::String(to_amount),
+            &Value::Null, // Deposits are plain transfers, no approval needed
+            &params["gasPrices"],
+            if quote_only { Some("quote") } else { None },
+            None,
+            None,
+            &state
+        ).await;
+
+        if let Err(e) = &formatted_result {
+            error!("Error formatting swap details: {}", e);
+        }
+
+        formatted_result.map_err(|e| e.context("Error formatting swap details"))
+    }.boxed()
+}
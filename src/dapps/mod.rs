@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use crate::dapps::adapter::DappAdapter;
+
+pub mod adapter;
+pub mod across;
+pub mod allbridge;
+pub mod balancer;
+pub mod bungee;
+pub mod debridge;
+pub mod hyphen;
+pub mod jumper;
+pub mod koi;
+pub mod layerswap;
+pub mod native;
+pub mod odos;
+pub mod owlto;
+pub mod stargate_v2;
+pub mod symbiosis;
+pub mod synapse;
+pub mod syncswap;
+pub mod wowmax;
+pub mod xy;
+
+pub struct AdapterRegistry {
+    adapters: HashMap<&'static str, Box<dyn DappAdapter>>,
+}
+
+impl AdapterRegistry {
+    // Adapters are keyed by their own name so the registry and `tool` can't drift apart
+    fn new(adapters: Vec<Box<dyn DappAdapter>>) -> Self {
+        AdapterRegistry {
+            adapters: adapters.into_iter().map(|adapter| (adapter.name(), adapter)).collect(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn DappAdapter> {
+        self.adapters.get(name).map(|adapter| adapter.as_ref())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.adapters.contains_key(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn DappAdapter> {
+        self.adapters.values().map(|adapter| adapter.as_ref())
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref ADAPTER_REGISTRY: AdapterRegistry = AdapterRegistry::new(vec![
+            Box::new(across::Across),
+            Box::new(allbridge::Allbridge),
+            Box::new(balancer::Balancer),
+            Box::new(bungee::Bungee),
+            Box::new(debridge::Debridge),
+            Box::new(hyphen::Hyphen),
+            Box::new(jumper::Jumper),
+            Box::new(koi::Koi),
+            Box::new(layerswap::Layerswap),
+            Box::new(native::Native),
+            Box::new(odos::Odos),
+            Box::new(owlto::Owlto),
+            Box::new(stargate_v2::StargateV2),
+            Box::new(symbiosis::Symbiosis),
+            Box::new(synapse::Synapse),
+            Box::new(syncswap::SyncSwap),
+            Box::new(wowmax::Wowmax),
+            Box::new(xy::Xy),
+    ]);
+}
+
+pub fn load_adapters() {
+    lazy_static::initialize(&ADAPTER_REGISTRY);
+    for adapter in ADAPTER_REGISTRY.iter() {
+        if let Some(chain_ids) = adapter.capabilities().bundled_chain_ids() {
+            tracing::info!("Loaded {} bundled chains for {}", chain_ids.len(), adapter.name());
+        }
+    }
+    tracing::info!("Registered {} dapp adapters", ADAPTER_REGISTRY.adapters.len());
+}
@@ -0,0 +1,184 @@
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tracing::{debug, error};
+use reqwest::Url;
+use crate::utils::utils::get_random_proxy_client;
+use crate::utils::format_swap_details::format_swap_details;
+use crate::utils::token_conversion::{format_units, parse_units};
+use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
+
+const FIRM_QUOTE_URL: &str = "https://newapi.native.org/v1/firm-quote";
+
+lazy_static::lazy_static! {
+    static ref CHAIN_SLUGS: HashMap<u64, String> = {
+        let chains_str = include_str!("./abi/native/chains.json");
+        let chains: Vec<Value> = serde_json::from_str(chains_str).expect("Failed to parse chains.json");
+        chains.iter()
+            .filter_map(|chain| Some((chain["chainId"].as_u64()?, chain["chain"].as_str()?.to_string())))
+            .collect()
+    };
+    // Token lists keyed by chain slug
+    static ref TOKENS: Value = {
+        let tokens_str = include_str!("./abi/native/tokens.json");
+        serde_json::from_str(tokens_str).expect("Failed to parse tokens.json")
+    };
+}
+
+// Native lists native tokens under the 0xeee address
+fn normalize_token_address(token_address: &str) -> String {
+    let zero_address = "0x0000000000000000000000000000000000000000";
+    let eee_address = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+    if token_address.to_lowercase() == zero_address {
+        eee_address.to_string()
+    } else {
+        token_address.to_lowercase()
+    }
+}
+
+fn find_token(chain: &str, token_address: &str) -> Option<&'static Value> {
+    let normalized = normalize_token_address(token_address);
+    TOKENS[chain].as_array()?
+        .iter()
+        .find(|token| token["address"].as_str().map(|address| address.to_lowercase()) == Some(normalized.clone()))
+}
+
+fn find_supported_token(chain: &str, token_address: &str) -> Result<&'static Value, QuoteError> {
+    let token = find_token(chain, token_address)
+        .ok_or_else(|| QuoteError::unsupported_pair(format!("Token {} is not listed by Native on {}", token_address, chain)))?;
+    if !token["isSupported"].as_bool().unwrap_or(false) {
+        return Err(QuoteError::unsupported_pair(format!("Token {} is not supported by Native on {}", token_address, chain)));
+    }
+    Ok(token)
+}
+
+// Native amounts come back either as numbers or numeric strings
+fn ui_amount_to_string(value: &Value) -> Option<String> {
+    value.as_str().map(|s| s.to_string()).or_else(|| value.as_f64().map(|n| n.to_string()))
+}
+
+// Firm quotes are backed by signed market maker orders, the earliest order deadline bounds the quote
+fn quote_expiry(result: &Value) -> Option<u64> {
+    result["deadline"].as_u64().or_else(|| {
+        result["orders"].as_array()?
+            .iter()
+            .filter_map(|order| order["deadlineTimestamp"].as_u64())
+            .min()
+    })
+}
+
+pub struct Native;
+
+impl DappAdapter for Native {
+    fn name(&self) -> &'static str {
+        "native"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Swap)
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+    async move {
+        let params = &request.params;
+        debug!("Starting get_swap_quote with params: {:?}", params);
+
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let from_address = request.from_address.as_str();
+        let slippage = request.slippage;
+        let quote_only = request.quote_only;
+
+        if from_chain_id != to_chain_id {
+            return Err(QuoteError::unsupported_pair("Native only supports same-chain swaps"));
+        }
+
+        let chain = CHAIN_SLUGS.get(&from_chain_id)
+            .ok_or_else(|| QuoteError::unsupported_pair(format!("Chain {} is not supported by Native", from_chain_id)))?;
+
+        let from_token = find_supported_token(chain, from_token_address)?;
+        let to_token = find_supported_token(chain, to_token_address)?;
+        let from_decimals = from_token["decimals"].as_u64().unwrap_or(18) as u8;
+        let to_decimals = to_token["decimals"].as_u64().unwrap_or(18) as u8;
+
+        // Native takes and returns human-readable amounts
+        let amount_raw = amount.parse::<u128>().map_err(|e| format!("Invalid amount: {}", e))?;
+        let ui_amount = format_units(amount_raw, from_decimals)?;
+
+        let client = get_random_proxy_client(&state.proxy_clients)
+            .ok_or_else(|| QuoteError::upstream("No proxy client available"))?;
+
+        let url = Url::parse(FIRM_QUOTE_URL).map_err(|e| e.to_string())?;
+        let query = [
+            ("chain", chain.clone()),
+            ("token_in", normalize_token_address(from_token_address)),
+            ("token_out", normalize_token_address(to_token_address)),
+            ("amount", ui_amount),
+            ("from_address", from_address.to_string()),
+            ("beneficiary_address", to_address.to_string()),
+            ("slippage", slippage.to_string()),
+        ];
+
+        let response = client.get(url)
+            .query(&query)
+            .header("apiKey", state.settings["native"]["apiKey"].as_str().unwrap_or(""))
+            .send()
+            .await
+            .map_err(|e| QuoteError::upstream(format!("Failed to fetch firm quote: {}", e)))?;
+
+        let status = response.status();
+        let result = response.json::<Value>().await
+            .map_err(|e| QuoteError::upstream(format!("Failed to parse firm quote response: {}", e)))?;
+
+        debug!("Native firm quote response: {:?}", result);
+
+        if !status.is_success() {
+            return Err(QuoteError::upstream(format!("Native API error: {}", result["message"].as_str().unwrap_or("unknown error"))));
+        }
+
+        let tx_request = &result["txRequest"];
+        let target = tx_request["target"].as_str().ok_or("Missing txRequest in Native firm quote")?;
+        let amount_out = ui_amount_to_string(&result["amountOut"]).ok_or("Missing amountOut in Native firm quote")?;
+        let to_amount = parse_units(&amount_out, to_decimals)?;
+        let expires_at = quote_expiry(&result).ok_or("Missing deadline in Native firm quote")?;
+
+        let transaction_data = json!({
+            "from": from_address,
+            "to": target,
+            "chainID": from_chain_id,
+            "data": tx_request["calldata"].as_str().ok_or("Missing calldata in Native firm quote")?,
+            "value": ui_amount_to_string(&tx_request["value"]).unwrap_or_else(|| "0".to_string()),
+        });
+
+        let formatted_result = format_swap_details(
+            "native",
+            params,
+            &transaction_data,
+            &Value::String(to_amount),
+            &Value::String(target.to_string()),
+            &params["gasPrices"],
+            if quote_only { Some("quote") } else { None },
+            None,
+            Some(&json!({ "expiresAt": expires_at })),
+            &state
+        ).await;
+
+        if let Err(e) = &formatted_result {
+            error!("Error formatting swap details: {}", e);
+        }
+
+        formatted_result.map_err(|e| e.context("Error formatting swap details"))
+    }.boxed()
+}
@@ -0,0 +1,203 @@
+use serde_json::{json, Value};
+use std::sync::Arc;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tracing::{debug, error};
+use reqwest::Client;
+use crate::utils::utils::get_random_proxy_client;
+use crate::utils::format_swap_details::format_swap_details;
+use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
+
+const API_URL: &str = "https://api.odos.xyz";
+
+lazy_static::lazy_static! {
+    static ref CHAIN_IDS: Vec<u64> = {
+        let paths_str = include_str!("./abi/odos/paths.json");
+        let paths: Value = serde_json::from_str(paths_str).expect("Failed to parse paths.json");
+        paths["chains"].as_array().expect("Invalid chains in paths.json")
+            .iter()
+            .filter_map(|id| id.as_u64())
+            .collect()
+    };
+}
+
+pub fn supported_chain_ids() -> Vec<u64> {
+    CHAIN_IDS.clone()
+}
+
+// Odos expects native tokens as the zero address
+fn normalize_token_address(token_address: &str) -> String {
+    let zero_address = "0x0000000000000000000000000000000000000000";
+    let eee_address = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+    if token_address.to_lowercase() == eee_address {
+        zero_address.to_string()
+    } else {
+        token_address.to_string()
+    }
+}
+
+async fn post_odos(client: &Client, endpoint: &str, body: &Value) -> Result<Value, QuoteError> {
+    let response = client.post(format!("{}{}", API_URL, endpoint))
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| QuoteError::upstream(format!("Failed to send request to {}: {}", endpoint, e)))?;
+
+    let status = response.status();
+    let result = response.json::<Value>().await
+        .map_err(|e| QuoteError::upstream(format!("Failed to parse {} response: {}", endpoint, e)))?;
+
+    if !status.is_success() {
+        error!("Odos API error on {}: {:?}", endpoint, result);
+        return Err(QuoteError::upstream(format!("Odos API error: {}", result["detail"].as_str().unwrap_or("unknown error"))));
+    }
+
+    Ok(result)
+}
+
+async fn get_router_address(client: &Client, chain_id: u64) -> Result<String, QuoteError> {
+    let result = client.get(format!("{}/info/router/v2/{}", API_URL, chain_id))
+        .send()
+        .await
+        .map_err(|e| QuoteError::upstream(format!("Failed to fetch router address: {}", e)))?
+        .json::<Value>()
+        .await
+        .map_err(|e| QuoteError::upstream(format!("Failed to parse router address response: {}", e)))?;
+
+    result["address"].as_str()
+        .map(|address| address.to_string())
+        .ok_or_else(|| QuoteError::upstream("Missing router address in Odos response"))
+}
+
+pub struct Odos;
+
+impl DappAdapter for Odos {
+    fn name(&self) -> &'static str {
+        "odos"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Swap).with_bundled_chains(supported_chain_ids())
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+    async move {
+        let params = &request.params;
+        debug!("Starting get_swap_quote with params: {:?}", params);
+
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let from_address = request.from_address.as_str();
+        let to_address = request.to_address.as_str();
+        let slippage = request.slippage;
+        let quote_only = request.quote_only;
+
+        if from_chain_id != to_chain_id {
+            return Err(QuoteError::unsupported_pair("Odos only supports same-chain swaps"));
+        }
+        if !CHAIN_IDS.contains(&from_chain_id) {
+            return Err(QuoteError::unsupported_pair(format!("Chain {} is not supported by Odos", from_chain_id)));
+        }
+
+        let client = get_random_proxy_client(&state.proxy_clients)
+            .ok_or_else(|| QuoteError::upstream("No proxy client available"))?;
+
+        let quote_body = json!({
+            "chainId": from_chain_id,
+            "inputTokens": [{
+                "tokenAddress": normalize_token_address(from_token_address),
+                "amount": amount,
+            }],
+            "outputTokens": [{
+                "tokenAddress": normalize_token_address(to_token_address),
+                "proportion": 1,
+            }],
+            "userAddr": from_address,
+            "slippageLimitPercent": slippage,
+            "referralCode": state.settings["odos"]["referralCode"].as_u64().unwrap_or(0),
+            "disableRFQs": true,
+            "compact": true,
+        });
+
+        debug!("Sending quote request to Odos API with body: {:?}", quote_body);
+
+        let quote = post_odos(&client, "/sor/quote/v2", &quote_body).await?;
+        let to_amount = quote["outAmounts"][0].as_str().ok_or("Missing outAmounts in Odos quote")?;
+
+        let formatted_result = if quote_only {
+            let router_address = get_router_address(&client, from_chain_id).await?;
+            let gas_estimate = (quote["gasEstimate"].as_f64().unwrap_or(0.0) as u64).to_string();
+
+            let quote_data = json!({
+                "from": from_address,
+                "to": router_address,
+                "chainID": from_chain_id,
+                "data": "quote",
+                "value": "quote",
+            });
+
+            format_swap_details(
+                "odos",
+                params,
+                &quote_data,
+                &Value::String(to_amount.to_string()),
+                &Value::String(router_address.clone()),
+                &params["gasPrices"],
+                Some(&gas_estimate),
+                None,
+                None,
+                &state
+            ).await
+        } else {
+            // The path id from the quote is only valid for a short while, assemble it right away
+            let path_id = quote["pathId"].as_str().ok_or("Missing pathId in Odos quote")?;
+            let assemble_body = json!({
+                "userAddr": from_address,
+                // Without a receiver Odos sends the output back to userAddr
+                "receiver": to_address,
+                "pathId": path_id,
+                "simulate": false,
+            });
+
+            let assembled = post_odos(&client, "/sor/assemble", &assemble_body).await?;
+            let transaction = &assembled["transaction"];
+            let router_address = transaction["to"].as_str().ok_or("Missing transaction in Odos assemble response")?;
+
+            let transaction_data = json!({
+                "from": from_address,
+                "to": router_address,
+                "chainID": from_chain_id,
+                "data": transaction["data"].as_str().unwrap_or("0x"),
+                "value": transaction["value"].as_str().unwrap_or("0"),
+            });
+
+            format_swap_details(
+                "odos",
+                params,
+                &transaction_data,
+                &Value::String(to_amount.to_string()),
+                &Value::String(router_address.to_string()),
+                &params["gasPrices"],
+                None,
+                None,
+                None,
+                &state
+            ).await
+        };
+
+        if let Err(e) = &formatted_result {
+            error!("Error formatting swap details: {}", e);
+        }
+
+        formatted_result.map_err(|e| e.context("Error formatting swap details"))
+    }.boxed()
+}
@@ -0,0 +1,204 @@
+use ethers::types::U256;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tracing::{debug, error};
+use crate::utils::utils::get_random_proxy_client;
+use crate::utils::format_swap_details::format_swap_details;
+use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
+
+const BUILD_TX_URL: &str = "https://owlto.finance/api/bridge_api/v1/get_build_tx";
+
+struct OwltoPair {
+    token_name: String,
+    from_chain_name: String,
+    to_chain_name: String,
+    to_token_address: String,
+    from_token_decimals: u32,
+    min_value: U256,
+    max_value: U256,
+    contract_address: String,
+}
+
+// (from chain id, to chain id, lowercase from token address)
+type PairKey = (u64, u64, String);
+
+lazy_static::lazy_static! {
+    static ref PAIRS: HashMap<PairKey, OwltoPair> = {
+        let paths_str = include_str!("./abi/owlto/paths.json");
+        let paths: Value = serde_json::from_str(paths_str).expect("Failed to parse paths.json");
+        let pair_infos = paths["data"]["pair_infos"].as_array().expect("Invalid pair_infos in paths.json");
+
+        let mut pairs = HashMap::with_capacity(pair_infos.len());
+        for pair in pair_infos {
+            let from_chain_id = pair["from_chain_id"].as_str().and_then(|id| id.parse::<u64>().ok());
+            let to_chain_id = pair["to_chain_id"].as_str().and_then(|id| id.parse::<u64>().ok());
+            let from_token_address = pair["from_token_address"].as_str();
+            let min_value = pair["min_value"]["raw_value"].as_str().and_then(|v| U256::from_dec_str(v).ok());
+            let max_value = pair["max_value"]["raw_value"].as_str().and_then(|v| U256::from_dec_str(v).ok());
+
+            if let (Some(from_chain_id), Some(to_chain_id), Some(from_token_address), Some(min_value), Some(max_value)) =
+                (from_chain_id, to_chain_id, from_token_address, min_value, max_value)
+            {
+                pairs.insert((from_chain_id, to_chain_id, from_token_address.to_lowercase()), OwltoPair {
+                    token_name: pair["token_name"].as_str().unwrap_or_default().to_string(),
+                    from_chain_name: pair["from_chain_name"].as_str().unwrap_or_default().to_string(),
+                    to_chain_name: pair["to_chain_name"].as_str().unwrap_or_default().to_string(),
+                    to_token_address: pair["to_token_address"].as_str().unwrap_or_default().to_lowercase(),
+                    from_token_decimals: pair["from_token_decimals"].as_u64().unwrap_or(18) as u32,
+                    min_value,
+                    max_value,
+                    contract_address: pair["contract_address"].as_str().unwrap_or_default().to_string(),
+                });
+            }
+        }
+        pairs
+    };
+}
+
+// Forces the pair index to be built so the first quote doesn't pay for parsing paths.json
+pub fn load_pairs() {
+    lazy_static::initialize(&PAIRS);
+    tracing::info!("Loaded {} Owlto pairs", PAIRS.len());
+}
+
+fn normalize_token_address(token_address: &str) -> String {
+    let zero_address = "0x0000000000000000000000000000000000000000";
+    let eee_address = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+    if token_address.to_lowercase() == eee_address {
+        zero_address.to_string()
+    } else {
+        token_address.to_lowercase()
+    }
+}
+
+fn format_ui_value(amount: U256, decimals: u32) -> String {
+    let divisor = U256::exp10(decimals as usize);
+    let whole = amount / divisor;
+    let fraction = amount % divisor;
+    if fraction.is_zero() {
+        return whole.to_string();
+    }
+    let fraction_str = format!("{:0>width$}", fraction.to_string(), width = decimals as usize);
+    format!("{}.{}", whole, fraction_str.trim_end_matches('0'))
+}
+
+pub struct Owlto;
+
+impl DappAdapter for Owlto {
+    fn name(&self) -> &'static str {
+        "owlto"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Bridge)
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+    async move {
+        let params = &request.params;
+        debug!("Starting get_swap_quote with params: {:?}", params);
+
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let from_address = request.from_address.as_str();
+        let quote_only = request.quote_only;
+
+        let pair = PAIRS.get(&(from_chain_id, to_chain_id, normalize_token_address(from_token_address)))
+            .ok_or_else(|| QuoteError::unsupported_pair("Token pair is not supported by Owlto"))?;
+
+        if pair.to_token_address != normalize_token_address(to_token_address) {
+            return Err(QuoteError::unsupported_pair("Owlto cannot deliver the requested toToken"));
+        }
+
+        // Reject out-of-range amounts before touching the network
+        let amount_in = U256::from_dec_str(amount).map_err(|e| format!("Invalid amount: {}", e))?;
+        if amount_in < pair.min_value || amount_in > pair.max_value {
+            return Err(QuoteError::out_of_limits("Amount is out of limits."));
+        }
+
+        let client = get_random_proxy_client(&state.proxy_clients)
+            .ok_or_else(|| QuoteError::upstream("No proxy client available"))?;
+
+        let mut body = json!({
+            "from_address": from_address,
+            "to_address": to_address,
+            "from_chain_name": pair.from_chain_name,
+            "to_chain_name": pair.to_chain_name,
+            "token_name": pair.token_name,
+            "ui_value": format_ui_value(amount_in, pair.from_token_decimals),
+            "value_include_gas_fee": true,
+        });
+        if let Some(channel) = state.settings["owlto"]["channel"].as_u64() {
+            body["channel"] = json!(channel);
+        }
+
+        debug!("Sending request to Owlto API with body: {:?}", body);
+
+        let response = client.post(BUILD_TX_URL)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| QuoteError::upstream(format!("Failed to send request: {}", e)))?;
+
+        let result = response.json::<Value>().await
+            .map_err(|e| QuoteError::upstream(format!("Failed to parse build tx response: {}", e)))?;
+
+        if result["code"].as_i64() != Some(0) {
+            error!("Owlto API error: {:?}", result);
+            return Err(QuoteError::upstream(format!("Owlto API error: {}", result["msg"].as_str().unwrap_or("unknown error"))));
+        }
+
+        let data = &result["data"];
+        let transfer_body = &data["txs"]["transfer_body"];
+        let to_amount = data["receive_value"]["raw_value"].as_str().ok_or("Missing receive value in build tx response")?;
+
+        // Owlto reports values either as decimal or hex strings
+        let value = match transfer_body["value"].as_str() {
+            Some(v) if v.starts_with("0x") => U256::from_str_radix(v.trim_start_matches("0x"), 16)
+                .map_err(|e| format!("Invalid transfer value: {}", e))?
+                .to_string(),
+            Some(v) => v.to_string(),
+            None => "0".to_string(),
+        };
+
+        let transaction_data = json!({
+            "from": from_address,
+            "to": transfer_body["to"],
+            "chainID": from_chain_id,
+            "data": transfer_body["data"].as_str().unwrap_or("0x"),
+            "value": value,
+        });
+
+        let formatted_result = format_swap_details(
+            "owlto",
+            params,
+            &transaction_data,
+            &Value::String(to_amount.to_string()),
+            &Value::String(pair.contract_address.clone()),
+            &params["gasPrices"],
+            if quote_only { Some("quote") } else { None },
+            None,
+            None,
+            &state
+        ).await;
+
+        if let Err(e) = &formatted_result {
+            error!("Error formatting swap details: {}", e);
+        }
+
+        formatted_result.map_err(|e| e.context("Error formatting swap details"))
+    }.boxed()
+}
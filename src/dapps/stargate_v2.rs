@@ -0,0 +1,231 @@
+use ethers::abi::{Abi, Token};
+use ethers::types::{Address, U256};
+use ethers::utils::hex;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::str::FromStr;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tracing::{debug, error};
+use crate::utils::utils::call_json_rpc;
+use crate::utils::format_swap_details::format_swap_details;
+use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
+
+lazy_static::lazy_static! {
+    static ref STARGATE_ABI: Abi = {
+        let abi_str = include_str!("./abi/stargate_v2/abi.json");
+        serde_json::from_str(abi_str).expect("Failed to parse ABI")
+    };
+    static ref TOKENS: Value = {
+        let tokens_str = include_str!("./abi/stargate_v2/tokens.json");
+        serde_json::from_str(tokens_str).expect("Failed to parse tokens.json")
+    };
+    static ref CHAINS: Vec<Value> = TOKENS["chains"].as_array()
+        .expect("Invalid chains in tokens.json")
+        .clone();
+}
+
+fn normalize_token_address(token_address: &str) -> String {
+    let zero_address = "0x0000000000000000000000000000000000000000";
+    let eee_address = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+    if token_address.to_lowercase() == eee_address {
+        zero_address.to_string()
+    } else {
+        token_address.to_lowercase()
+    }
+}
+
+fn find_chain(chain_id: u64) -> Option<&'static Value> {
+    CHAINS.iter().find(|chain| chain["chainId"].as_u64() == Some(chain_id))
+}
+
+// Returns the asset symbol and StargatePool address for a token on the given chain
+fn find_asset(chain: &Value, token_address: &str) -> Option<(String, String)> {
+    let normalized = normalize_token_address(token_address);
+    chain["contracts"].as_object()?.iter()
+        .find(|(_, contract)| contract["token"].as_str().map(|t| t.to_lowercase()) == Some(normalized.clone()))
+        .and_then(|(symbol, contract)| {
+            contract["StargatePool"].as_str().map(|pool| (symbol.clone(), pool.to_string()))
+        })
+}
+
+fn address_to_bytes32(address: Address) -> Vec<u8> {
+    let mut bytes = vec![0u8; 12];
+    bytes.extend_from_slice(address.as_bytes());
+    bytes
+}
+
+fn build_send_param(dst_eid: u32, to: Address, amount_ld: U256, min_amount_ld: U256) -> Token {
+    Token::Tuple(vec![
+        Token::Uint(U256::from(dst_eid)),
+        Token::FixedBytes(address_to_bytes32(to)),
+        Token::Uint(amount_ld),
+        Token::Uint(min_amount_ld),
+        Token::Bytes(vec![]), // No extra options
+        Token::Bytes(vec![]), // No compose message
+        Token::Bytes(vec![]), // Empty oftCmd selects taxi mode
+    ])
+}
+
+async fn call_pool(
+    chain_id: u64,
+    pool_address: &str,
+    function_name: &str,
+    inputs: &[Token],
+    state: &Arc<AppState>
+) -> Result<Vec<Token>, String> {
+    let function = STARGATE_ABI.function(function_name)
+        .map_err(|e| format!("Failed to get function {}: {}", function_name, e))?;
+    let data = function.encode_input(inputs)
+        .map_err(|e| format!("Failed to encode {}: {}", function_name, e))?;
+
+    let output = call_json_rpc(chain_id, pool_address, &data, &state.rpc_proxy_providers).await
+        .map_err(|e| format!("{} call failed: {}", function_name, e))?;
+
+    function.decode_output(&output)
+        .map_err(|e| format!("Failed to decode {} output: {}", function_name, e))
+}
+
+fn tuple_uint(token: Option<&Token>, index: usize) -> Option<U256> {
+    match token {
+        Some(Token::Tuple(values)) => values.get(index).and_then(|v| v.clone().into_uint()),
+        _ => None,
+    }
+}
+
+pub struct StargateV2;
+
+impl DappAdapter for StargateV2 {
+    fn name(&self) -> &'static str {
+        "stargate_v2"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Bridge)
+    }
+
+    fn error_abis(&self) -> Vec<&'static Abi> {
+        vec![&STARGATE_ABI]
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+    async move {
+        let params = &request.params;
+        debug!("Starting get_swap_quote with params: {:?}", params);
+
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let from_address = request.from_address.as_str();
+        let quote_only = request.quote_only;
+        let slippage = request.slippage;
+
+        if from_chain_id == to_chain_id {
+            return Err(QuoteError::unsupported_pair("Stargate V2 only supports cross-chain transfers"));
+        }
+
+        let from_chain = find_chain(from_chain_id)
+            .ok_or_else(|| QuoteError::unsupported_pair(format!("Chain {} is not supported by Stargate V2", from_chain_id)))?;
+        let to_chain = find_chain(to_chain_id)
+            .ok_or_else(|| QuoteError::unsupported_pair(format!("Chain {} is not supported by Stargate V2", to_chain_id)))?;
+        let dst_eid = to_chain["id"].as_u64().ok_or("Invalid endpoint id in tokens.json")? as u32;
+
+        let (asset_symbol, pool_address) = find_asset(from_chain, from_token_address)
+            .ok_or_else(|| QuoteError::unsupported_pair("fromToken is not supported by Stargate V2"))?;
+        let (to_asset_symbol, _) = find_asset(to_chain, to_token_address)
+            .ok_or_else(|| QuoteError::unsupported_pair("toToken is not supported by Stargate V2"))?;
+
+        if asset_symbol != to_asset_symbol {
+            return Err(QuoteError::unsupported_pair(format!("Stargate V2 cannot bridge {} to {}", asset_symbol, to_asset_symbol)));
+        }
+
+        debug!("Stargate V2 route: asset={}, pool={}, dstEid={}", asset_symbol, pool_address, dst_eid);
+
+        let amount_ld = U256::from_dec_str(amount).map_err(|e| format!("Invalid amount: {}", e))?;
+        let recipient = Address::from_str(to_address).map_err(|e| format!("Invalid toAddress: {}", e))?;
+        let refund_address = Address::from_str(from_address).map_err(|e| format!("Invalid fromAddress: {}", e))?;
+
+        // Quote the OFT transfer to learn the limits and the amount delivered on the destination chain
+        let oft_quote = call_pool(
+            from_chain_id,
+            &pool_address,
+            "quoteOFT",
+            &[build_send_param(dst_eid, recipient, amount_ld, amount_ld)],
+            &state
+        ).await.map_err(QuoteError::upstream)?;
+
+        let min_amount = tuple_uint(oft_quote.first(), 0).ok_or("Missing minAmountLD in quoteOFT")?;
+        let max_amount = tuple_uint(oft_quote.first(), 1).ok_or("Missing maxAmountLD in quoteOFT")?;
+        let amount_received = tuple_uint(oft_quote.get(2), 1).ok_or("Missing amountReceivedLD in quoteOFT")?;
+
+        if amount_ld < min_amount || amount_ld > max_amount {
+            return Err(QuoteError::out_of_limits("Amount is out of limits."));
+        }
+
+        let slippage_bps = ((100.0 - slippage) * 100.0).max(0.0) as u64;
+        let min_amount_received = amount_received * U256::from(slippage_bps) / U256::from(10_000u64);
+        let send_param = build_send_param(dst_eid, recipient, amount_ld, min_amount_received);
+
+        // Quote the LayerZero messaging fee, paid in the native token
+        let send_quote = call_pool(
+            from_chain_id,
+            &pool_address,
+            "quoteSend",
+            &[send_param.clone(), Token::Bool(false)],
+            &state
+        ).await.map_err(QuoteError::upstream)?;
+
+        let native_fee = tuple_uint(send_quote.first(), 0).ok_or("Missing nativeFee in quoteSend")?;
+
+        debug!("Stargate V2 quote: amountReceivedLD={}, nativeFee={}", amount_received, native_fee);
+
+        let send_function = STARGATE_ABI.function("send")
+            .map_err(|e| format!("Failed to get function send: {}", e))?;
+        let encoded = send_function.encode_input(&[
+            send_param,
+            Token::Tuple(vec![Token::Uint(native_fee), Token::Uint(U256::zero())]),
+            Token::Address(refund_address),
+        ]).map_err(|e| format!("Failed to encode send: {}", e))?;
+
+        let is_from_eth = normalize_token_address(from_token_address) == "0x0000000000000000000000000000000000000000";
+        let value = if is_from_eth { amount_ld + native_fee } else { native_fee };
+
+        let transaction_data = json!({
+            "from": from_address,
+            "to": pool_address,
+            "chainID": from_chain_id,
+            "data": format!("0x{}", hex::encode(encoded)),
+            "value": value.to_string(),
+        });
+
+        let native_fee_value = Value::String(native_fee.to_string());
+
+        let formatted_result = format_swap_details(
+            "stargate_v2",
+            params,
+            &transaction_data,
+            &Value::String(amount_received.to_string()),
+            &Value::String(pool_address.clone()),
+            &params["gasPrices"],
+            if quote_only { Some("quote") } else { None },
+            Some(&native_fee_value),
+            None,
+            &state
+        ).await;
+
+        if let Err(e) = &formatted_result {
+            error!("Error formatting swap details: {}", e);
+        }
+
+        formatted_result.map_err(|e| e.context("Error formatting swap details"))
+    }.boxed()
+}
@@ -0,0 +1,165 @@
+use serde_json::{json, Value};
+use std::sync::Arc;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tracing::{debug, error};
+use crate::utils::utils::{get_random_proxy_client, BITCOIN_CHAIN_ID};
+use crate::utils::fetch_token_details::TokenInfo;
+use crate::utils::format_swap_details::format_swap_details;
+use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
+
+const SWAP_URL: &str = "https://api.symbiosis.finance/crosschain/v1/swap";
+
+lazy_static::lazy_static! {
+    static ref CHAINS: Vec<Value> = {
+        let paths_str = include_str!("./abi/symbiosis/paths.json");
+        serde_json::from_str(paths_str).expect("Failed to parse paths.json")
+    };
+}
+
+fn is_supported_chain(chain_id: u64) -> bool {
+    CHAINS.iter().any(|chain| chain["id"].as_u64() == Some(chain_id))
+}
+
+// Symbiosis expects native tokens as an empty address
+fn to_symbiosis_address(token_address: &str) -> String {
+    let zero_address = "0x0000000000000000000000000000000000000000";
+    let eee_address = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+    let address = token_address.to_lowercase();
+    if address == zero_address || address == eee_address {
+        String::new()
+    } else {
+        token_address.to_string()
+    }
+}
+
+// Bitcoin has no token list or RPC to read from, so its details are fixed
+pub fn bitcoin_token_details() -> TokenInfo {
+    let logo_uri = CHAINS.iter()
+        .find(|chain| chain["id"].as_u64() == Some(BITCOIN_CHAIN_ID))
+        .and_then(|chain| chain["icon"].as_str())
+        .unwrap_or_default();
+
+    TokenInfo {
+        address: "0x0000000000000000000000000000000000000000".to_string(),
+        chain_id: BITCOIN_CHAIN_ID,
+        symbol: "BTC".to_string(),
+        decimals: 8,
+        name: "Bitcoin".to_string(),
+        coin_key: "BTC".to_string(),
+        logo_uri: logo_uri.to_string(),
+        price_usd: None,
+    }
+}
+
+pub struct Symbiosis;
+
+impl DappAdapter for Symbiosis {
+    fn name(&self) -> &'static str {
+        "symbiosis"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Any)
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+    async move {
+        let params = &request.params;
+        debug!("Starting get_swap_quote with params: {:?}", params);
+
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let from_address = request.from_address.as_str();
+        let slippage = request.slippage;
+        let quote_only = request.quote_only;
+
+        if !is_supported_chain(from_chain_id) || !is_supported_chain(to_chain_id) {
+            return Err(QuoteError::unsupported_pair("Chain pair is not supported by Symbiosis"));
+        }
+
+        let from_decimals = params["fromTokenDetails"]["decimals"].as_u64().ok_or("Missing fromTokenDetails")?;
+        let to_decimals = params["toTokenDetails"]["decimals"].as_u64().ok_or("Missing toTokenDetails")?;
+
+        // BTC is the native asset of its chain, so it is addressed like any other native token
+        let body = json!({
+            "tokenAmountIn": {
+                "chainId": from_chain_id,
+                "address": to_symbiosis_address(from_token_address),
+                "decimals": from_decimals,
+                "amount": amount,
+            },
+            "tokenOut": {
+                "chainId": to_chain_id,
+                "address": to_symbiosis_address(to_token_address),
+                "symbol": params["toTokenDetails"]["symbol"],
+                "decimals": to_decimals,
+            },
+            "from": from_address,
+            "to": to_address,
+            "slippage": (slippage * 100.0).max(0.0) as u64,
+        });
+
+        let client = get_random_proxy_client(&state.proxy_clients)
+            .ok_or_else(|| QuoteError::upstream("No proxy client available"))?;
+
+        debug!("Sending request to Symbiosis API with body: {:?}", body);
+
+        let response = client.post(SWAP_URL)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| QuoteError::upstream(format!("Failed to send request: {}", e)))?;
+
+        let status = response.status();
+        let result = response.json::<Value>().await
+            .map_err(|e| QuoteError::upstream(format!("Failed to parse swap response: {}", e)))?;
+
+        if !status.is_success() {
+            error!("Symbiosis API error: {:?}", result);
+            return Err(QuoteError::upstream(format!("Symbiosis API error: {}", result["message"].as_str().unwrap_or("unknown error"))));
+        }
+
+        let tx = &result["tx"];
+        let to_amount = result["tokenAmountOut"]["amount"].as_str().ok_or("Missing tokenAmountOut in swap response")?;
+
+        let transaction_data = json!({
+            "from": from_address,
+            "to": tx["to"],
+            "chainID": from_chain_id,
+            "data": tx["data"].as_str().unwrap_or("0x"),
+            "value": tx["value"].as_str().unwrap_or("0"),
+        });
+
+        let dapp_options = json!({ "estimatedTime": result["estimatedTime"] });
+
+        let formatted_result = format_swap_details(
+            "symbiosis",
+            params,
+            &transaction_data,
+            &Value::String(to_amount.to_string()),
+            &result["approveTo"],
+            &params["gasPrices"],
+            if quote_only { Some("quote") } else { None },
+            None,
+            Some(&dapp_options),
+            &state
+        ).await;
+
+        if let Err(e) = &formatted_result {
+            error!("Error formatting swap details: {}", e);
+        }
+
+        formatted_result.map_err(|e| e.context("Error formatting swap details"))
+    }.boxed()
+}
@@ -0,0 +1,201 @@
+use ethers::types::U256;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tracing::{debug, error};
+use reqwest::Url;
+use crate::utils::utils::get_random_proxy_client;
+use crate::utils::format_swap_details::format_swap_details;
+use crate::utils::token_conversion::format_units;
+use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
+
+const BRIDGE_URL: &str = "https://api.synapseprotocol.com/bridge";
+
+lazy_static::lazy_static! {
+    static ref PATHS: Value = {
+        let paths_str = include_str!("./abi/synapse/paths.json");
+        serde_json::from_str(paths_str).expect("Failed to parse paths.json")
+    };
+}
+
+fn is_native_address(token_address: &str) -> bool {
+    let zero_address = "0x0000000000000000000000000000000000000000";
+    let eee_address = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+    let address = token_address.to_lowercase();
+    address == zero_address || address == eee_address
+}
+
+fn is_supported_chain(chain_id: u64) -> bool {
+    PATHS["chains"].as_array()
+        .map(|chains| chains.iter().any(|chain| chain["id"].as_u64() == Some(chain_id)))
+        .unwrap_or(false)
+}
+
+fn token_address_on_chain(token: &Value, chain_id: u64) -> Option<&str> {
+    token["chains"].as_array()?
+        .iter()
+        .find(|chain| chain["chainId"].as_u64() == Some(chain_id))
+        .and_then(|chain| chain["address"].as_str())
+}
+
+fn matches_address(listed: &str, requested: &str) -> bool {
+    listed.to_lowercase() == requested.to_lowercase() || (is_native_address(listed) && is_native_address(requested))
+}
+
+// Resolves the addresses Synapse lists for the same asset on both chains
+fn find_token_pair(from_chain_id: u64, to_chain_id: u64, from_token_address: &str, to_token_address: &str) -> Option<(&'static str, &'static str)> {
+    PATHS["tokens"].as_array()?.iter().find_map(|token| {
+        let from_address = token_address_on_chain(token, from_chain_id)?;
+        let to_address = token_address_on_chain(token, to_chain_id)?;
+        if matches_address(from_address, from_token_address) && matches_address(to_address, to_token_address) {
+            Some((from_address, to_address))
+        } else {
+            None
+        }
+    })
+}
+
+// Synapse returns amounts either as ethers BigNumber objects or plain strings
+fn parse_big_number(value: &Value) -> Option<U256> {
+    let raw = value["hex"].as_str().or_else(|| value.as_str())?;
+    if let Some(hex) = raw.strip_prefix("0x") {
+        U256::from_str_radix(hex, 16).ok()
+    } else {
+        U256::from_dec_str(raw).ok()
+    }
+}
+
+fn api_error_message(result: &Value) -> String {
+    result["error"]["message"].as_str()
+        .or_else(|| result["error"].as_str())
+        .or_else(|| result["message"].as_str())
+        .unwrap_or("unknown error")
+        .to_string()
+}
+
+pub struct Synapse;
+
+impl DappAdapter for Synapse {
+    fn name(&self) -> &'static str {
+        "synapse"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Bridge)
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+    async move {
+        let params = &request.params;
+        debug!("Starting get_swap_quote with params: {:?}", params);
+
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let from_address = request.from_address.as_str();
+        let quote_only = request.quote_only;
+
+        if from_chain_id == to_chain_id {
+            return Err(QuoteError::unsupported_pair("Synapse only supports cross-chain transfers"));
+        }
+        if !is_supported_chain(from_chain_id) || !is_supported_chain(to_chain_id) {
+            return Err(QuoteError::unsupported_pair("Chain pair is not supported by Synapse"));
+        }
+
+        let (from_token, to_token) = find_token_pair(from_chain_id, to_chain_id, from_token_address, to_token_address)
+            .ok_or_else(|| QuoteError::unsupported_pair("Token pair is not supported by Synapse"))?;
+
+        // The bridge endpoint takes human-readable amounts
+        let from_decimals = params["fromTokenDetails"]["decimals"].as_u64().ok_or("Missing fromTokenDetails")? as u8;
+        let amount_raw = amount.parse::<u128>().map_err(|e| format!("Invalid amount: {}", e))?;
+        let ui_amount = format_units(amount_raw, from_decimals)?;
+
+        let client = get_random_proxy_client(&state.proxy_clients)
+            .ok_or_else(|| QuoteError::upstream("No proxy client available"))?;
+
+        let url = Url::parse(BRIDGE_URL).map_err(|e| e.to_string())?;
+        let query = [
+            ("fromChain", from_chain_id.to_string()),
+            ("toChain", to_chain_id.to_string()),
+            ("fromToken", from_token.to_string()),
+            ("toToken", to_token.to_string()),
+            ("amount", ui_amount),
+            ("originUserAddress", from_address.to_string()),
+            ("destAddress", to_address.to_string()),
+        ];
+
+        let response = client.get(url)
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| QuoteError::upstream(format!("Failed to fetch Synapse bridge quote: {}", e)))?;
+
+        let status = response.status();
+        let result = response.json::<Value>().await
+            .map_err(|e| QuoteError::upstream(format!("Failed to parse Synapse bridge response (HTTP {}): {}", status, e)))?;
+
+        debug!("Synapse bridge response: {:?}", result);
+
+        if !status.is_success() {
+            return Err(QuoteError::upstream(format!("Synapse API error: {}", api_error_message(&result))));
+        }
+
+        // Several bridge modules may quote the pair, keep the best output
+        let best_quote = result.as_array()
+            .and_then(|quotes| quotes.iter()
+                .filter_map(|quote| parse_big_number(&quote["maxAmountOut"]).map(|out| (out, quote)))
+                .max_by_key(|(out, _)| *out))
+            .ok_or_else(|| QuoteError::unsupported_pair("Synapse returned no route for the requested pair"))?;
+
+        let (to_amount, quote) = best_quote;
+        let call_data = &quote["callData"];
+        let router_address = quote["routerAddress"].as_str().ok_or("Missing routerAddress in Synapse quote")?;
+        let bridge_fee = parse_big_number(&quote["feeAmount"]).unwrap_or_default();
+
+        debug!("Synapse quote via {}: amountOut={}, fee={}", quote["bridgeModuleName"], to_amount, bridge_fee);
+
+        let value = parse_big_number(&call_data["value"]).unwrap_or_default();
+        let transaction_data = json!({
+            "from": from_address,
+            "to": call_data["to"].as_str().unwrap_or(router_address),
+            "chainID": from_chain_id,
+            "data": call_data["data"].as_str().ok_or("Missing callData in Synapse quote")?,
+            "value": value.to_string(),
+        });
+
+        // The bridge fee is charged in the bridged token and already deducted from maxAmountOut
+        let dapp_options = json!({
+            "bridgeFee": bridge_fee.to_string(),
+            "estimatedTime": quote["estimatedTime"],
+        });
+
+        let formatted_result = format_swap_details(
+            "synapse",
+            params,
+            &transaction_data,
+            &Value::String(to_amount.to_string()),
+            &Value::String(router_address.to_string()),
+            &params["gasPrices"],
+            if quote_only { Some("quote") } else { None },
+            None,
+            Some(&dapp_options),
+            &state
+        ).await;
+
+        if let Err(e) = &formatted_result {
+            error!("Error formatting swap details: {}", e);
+        }
+
+        formatted_result.map_err(|e| e.context("Error formatting swap details"))
+    }.boxed()
+}
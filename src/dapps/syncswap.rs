@@ -0,0 +1,328 @@
+use ethers::prelude::*;
+use ethers::abi::Token;
+use ethers::utils::hex;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::str::FromStr;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tracing::debug;
+use crate::utils::utils::get_random_rpc_proxy_provider;
+use crate::utils::format_swap_details::{format_swap_details, max_amount_in};
+use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, PermitKind, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
+
+// Supported chains with their router and classic pool factory deployments
+const ZKSYNC_CHAIN_ID: u64 = 324;
+const LINEA_CHAIN_ID: u64 = 59144;
+const SCROLL_CHAIN_ID: u64 = 534352;
+
+const ZKSYNC_ROUTER_ADDRESS: &str = "0x2da10A1e27bF85cEdD8FFb1AbBe97e53391C0295";
+const ZKSYNC_FACTORY_ADDRESS: &str = "0xf2DAd89f2788a8CD54625C60b55cD3d2D0ACa7Cb";
+const LINEA_ROUTER_ADDRESS: &str = "0x80e38291e06339d10AAB483C65695D004dBD5C69";
+const LINEA_FACTORY_ADDRESS: &str = "0x37BAc764494c8db4e54BDE72f6965beA9fa0AC2d";
+const SCROLL_ROUTER_ADDRESS: &str = "0x80e38291e06339d10AAB483C65695D004dBD5C69";
+const SCROLL_FACTORY_ADDRESS: &str = "0x37BAc764494c8db4e54BDE72f6965beA9fa0AC2d";
+
+// Withdraw modes understood by SyncSwap pools
+const WITHDRAW_MODE_UNWRAP_ETH: u8 = 1;
+const WITHDRAW_MODE_WRAPPED: u8 = 2;
+
+lazy_static::lazy_static! {
+    static ref ROUTER_ZKSYNC_ABI: ethers::abi::Abi = {
+        let abi_str = include_str!("./abi/syncswap/routerZksync.json");
+        serde_json::from_str(abi_str).expect("Failed to parse ABI")
+    };
+    static ref ROUTER_LINEA_ABI: ethers::abi::Abi = {
+        let abi_str = include_str!("./abi/syncswap/routerLinea.json");
+        serde_json::from_str(abi_str).expect("Failed to parse ABI")
+    };
+    static ref ROUTER_SCROLL_ABI: ethers::abi::Abi = {
+        let abi_str = include_str!("./abi/syncswap/routerScroll.json");
+        serde_json::from_str(abi_str).expect("Failed to parse ABI")
+    };
+    static ref FACTORY_ABI: ethers::abi::Abi = {
+        let abi_str = include_str!("./abi/syncswap/factory.json");
+        serde_json::from_str(abi_str).expect("Failed to parse ABI")
+    };
+    static ref POOL_ABI: ethers::abi::Abi = {
+        let abi_str = include_str!("./abi/syncswap/pool.json");
+        serde_json::from_str(abi_str).expect("Failed to parse ABI")
+    };
+}
+
+struct SyncSwapDeployment {
+    router: &'static str,
+    factory: &'static str,
+    router_abi: &'static ethers::abi::Abi,
+    // The zkSync router carries an extra `useVault` flag on every swap step
+    step_uses_vault: bool,
+}
+
+fn get_deployment(chain_id: u64) -> Option<SyncSwapDeployment> {
+    match chain_id {
+        ZKSYNC_CHAIN_ID => Some(SyncSwapDeployment {
+            router: ZKSYNC_ROUTER_ADDRESS,
+            factory: ZKSYNC_FACTORY_ADDRESS,
+            router_abi: &ROUTER_ZKSYNC_ABI,
+            step_uses_vault: true,
+        }),
+        LINEA_CHAIN_ID => Some(SyncSwapDeployment {
+            router: LINEA_ROUTER_ADDRESS,
+            factory: LINEA_FACTORY_ADDRESS,
+            router_abi: &ROUTER_LINEA_ABI,
+            step_uses_vault: false,
+        }),
+        SCROLL_CHAIN_ID => Some(SyncSwapDeployment {
+            router: SCROLL_ROUTER_ADDRESS,
+            factory: SCROLL_FACTORY_ADDRESS,
+            router_abi: &ROUTER_SCROLL_ABI,
+            step_uses_vault: false,
+        }),
+        _ => None,
+    }
+}
+
+// The input the router call sends and the least output it accepts.
+// The router only swaps exact inputs, so an exact-output swap sends up to the slippage bound and requires the full toAmount
+fn swap_amounts(amount_in: U256, amount_out: U256, exact_out: bool, slippage: f64) -> (U256, U256) {
+    if exact_out {
+        (max_amount_in(amount_in, slippage), amount_out)
+    } else {
+        let slippage_bps = ((100.0 - slippage) * 100.0).max(0.0) as u64;
+        (amount_in, amount_out * U256::from(slippage_bps) / U256::from(10_000u64))
+    }
+}
+
+pub struct SyncSwap;
+
+impl DappAdapter for SyncSwap {
+    fn name(&self) -> &'static str {
+        "syncswap"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Swap).with_exact_out().with_permits(vec![PermitKind::Eip2612])
+    }
+
+    fn error_abis(&self) -> Vec<&'static ethers::abi::Abi> {
+        vec![&ROUTER_ZKSYNC_ABI, &ROUTER_LINEA_ABI, &ROUTER_SCROLL_ABI, &POOL_ABI]
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+    async move {
+        let params = &request.params;
+        debug!("Starting get_swap_quote with params: {:?}", params);
+
+        let from_chain_id = request.from_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let from_address = request.from_address.as_str();
+        let quote_only = request.quote_only;
+        let slippage = request.slippage;
+
+        let deployment = get_deployment(from_chain_id)
+            .ok_or_else(|| QuoteError::unsupported_pair(format!("SyncSwap is not deployed on chain {}", from_chain_id)))?;
+
+        let zero_address = "0x0000000000000000000000000000000000000000";
+        let eth_marker_address = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+
+        let is_from_eth = [zero_address, eth_marker_address].contains(&from_token_address.to_lowercase().as_str());
+        let is_to_eth = [zero_address, eth_marker_address].contains(&to_token_address.to_lowercase().as_str());
+
+        let provider = get_random_rpc_proxy_provider(from_chain_id, &state.rpc_proxy_providers)
+            .ok_or_else(|| QuoteError::upstream("No RPC provider available"))?;
+        let router_address = Address::from_str(deployment.router).map_err(|e| e.to_string())?;
+        let router_contract = Contract::new(router_address, deployment.router_abi.clone(), provider.clone());
+        let factory_contract = Contract::new(Address::from_str(deployment.factory).map_err(|e| e.to_string())?, FACTORY_ABI.clone(), provider.clone());
+
+        // Pools only hold wETH, so native ETH is routed through the router's wETH
+        let weth_address: Address = router_contract.method::<_, Address>("wETH", ())
+            .map_err(|e| e.to_string())?
+            .call()
+            .await
+            .map_err(|e| QuoteError::upstream(format!("Failed to get wETH address: {}", e)))?;
+
+        let token_in = if is_from_eth { weth_address } else { Address::from_str(from_token_address).map_err(|e| e.to_string())? };
+        let token_out = if is_to_eth { weth_address } else { Address::from_str(to_token_address).map_err(|e| e.to_string())? };
+        let sender = Address::from_str(from_address).map_err(|e| e.to_string())?;
+        let recipient = Address::from_str(to_address).map_err(|e| e.to_string())?;
+
+        let pool_address: Address = factory_contract.method::<_, Address>("getPool", (token_in, token_out))
+            .map_err(|e| e.to_string())?
+            .call()
+            .await
+            .map_err(|e| QuoteError::upstream(format!("Failed to get pool: {}", e)))?;
+
+        if pool_address == Address::zero() {
+            return Err(QuoteError::unsupported_pair("No SyncSwap pool found for token pair"));
+        }
+
+        debug!("Resolved SyncSwap pool: {:?}", pool_address);
+
+        let pool_contract = Contract::new(pool_address, POOL_ABI.clone(), provider.clone());
+
+        // Exact-output quotes ask the pool for the input that buys toAmount
+        let (amount_in, amount_out) = match request.to_amount.as_deref() {
+            Some(to_amount) => {
+                let amount_out = U256::from_dec_str(to_amount).map_err(|e| format!("Invalid toAmount: {}", e))?;
+                let amount_in: U256 = pool_contract.method::<_, U256>("getAmountIn", (token_out, amount_out, sender))
+                    .map_err(|e| e.to_string())?
+                    .call()
+                    .await
+                    .map_err(|e| QuoteError::upstream(format!("Failed to get amount in: {}", e)))?;
+                (amount_in, amount_out)
+            }
+            None => {
+                let amount_in = U256::from_dec_str(amount).map_err(|e| e.to_string())?;
+                let amount_out: U256 = pool_contract.method::<_, U256>("getAmountOut", (token_in, amount_in, sender))
+                    .map_err(|e| e.to_string())?
+                    .call()
+                    .await
+                    .map_err(|e| QuoteError::upstream(format!("Failed to get amount out: {}", e)))?;
+                (amount_in, amount_out)
+            }
+        };
+
+        if amount_in.is_zero() || amount_out.is_zero() {
+            return Err(QuoteError::out_of_limits("Insufficient liquidity in SyncSwap pool"));
+        }
+
+        let dapp_options = request.is_exact_out().then(|| json!({ "fromAmount": amount_in.to_string() }));
+
+        if quote_only {
+            let quote_data = json!({
+                "from": from_address,
+                "to": deployment.router,
+                "chainID": from_chain_id,
+                "data": "quote",
+                "value": "quote",
+            });
+            format_swap_details(
+                "syncswap",
+                params,
+                &quote_data,
+                &Value::String(amount_out.to_string()),
+                &Value::String(deployment.router.to_string()),
+                &params["gasPrices"],
+                Some("quote"),
+                None,
+                dapp_options.as_ref(),
+                &state
+            ).await.map_err(|e| e.context("Failed to format swap details"))
+        } else {
+            let (amount_in, amount_out_min) = swap_amounts(amount_in, amount_out, request.is_exact_out(), slippage);
+
+            let deadline = U256::from(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() + 1800);
+
+            let withdraw_mode = if is_to_eth { WITHDRAW_MODE_UNWRAP_ETH } else { WITHDRAW_MODE_WRAPPED };
+            let swap_data = ethers::abi::encode(&[
+                Token::Address(token_in),
+                Token::Address(recipient),
+                Token::Uint(U256::from(withdraw_mode)),
+            ]);
+
+            let mut step = vec![
+                Token::Address(pool_address),
+                Token::Bytes(swap_data),
+                Token::Address(Address::zero()), // No callback
+                Token::Bytes(vec![]),
+            ];
+            if deployment.step_uses_vault {
+                step.push(Token::Bool(false));
+            }
+
+            // The path tokenIn must be the zero address for the router to wrap msg.value
+            let path_token_in = if is_from_eth { Address::zero() } else { token_in };
+            let paths = Token::Array(vec![Token::Tuple(vec![
+                Token::Array(vec![Token::Tuple(step)]),
+                Token::Address(path_token_in),
+                Token::Uint(amount_in),
+            ])]);
+
+            let mut function_params = vec![
+                paths,
+                Token::Uint(amount_out_min),
+                Token::Uint(deadline),
+            ];
+
+            // A signed EIP-2612 permit lets the router pull tokenIn without a prior approve
+            let function_name = match request.permit.as_ref().filter(|_| !is_from_eth) {
+                Some(permit) => {
+                    let (v, r, s) = permit.split();
+                    function_params.push(Token::Tuple(vec![
+                        Token::Address(token_in),
+                        Token::Uint(permit.value),
+                        Token::Uint(permit.deadline),
+                        Token::Uint(U256::from(v)),
+                        Token::FixedBytes(r.to_vec()),
+                        Token::FixedBytes(s.to_vec()),
+                    ]));
+                    "swapWithPermit"
+                }
+                None => "swap",
+            };
+
+            let function = router_contract.abi().function(function_name)
+                .map_err(|e| format!("Failed to get function {}: {:?}", function_name, e))?;
+
+            let encoded = function.encode_input(&function_params)
+                .map_err(|e| format!("Failed to encode function call: {}. Function: {}, Params: {:?}", e, function_name, function_params))?;
+
+            let tx = json!({
+                "from": from_address,
+                "to": deployment.router,
+                "data": format!("0x{}", hex::encode(encoded)),
+                "value": if is_from_eth { amount_in.to_string() } else { "0".to_string() },
+            });
+
+            format_swap_details(
+                "syncswap",
+                params,
+                &tx,
+                &Value::String(amount_out.to_string()),
+                &Value::String(deployment.router.to_string()),
+                &params["gasPrices"],
+                None,
+                None,
+                dapp_options.as_ref(),
+                &state
+            ).await.map_err(|e| e.context("Failed to format swap details"))
+        }
+    }.boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_input_swaps_apply_slippage_to_the_output() {
+        let (amount_in, amount_out_min) = swap_amounts(U256::from(1_000u64), U256::from(2_000u64), false, 0.5);
+        assert_eq!(amount_in, U256::from(1_000u64));
+        assert_eq!(amount_out_min, U256::from(1_990u64));
+    }
+
+    #[test]
+    fn exact_output_swaps_send_up_to_the_max_input_for_the_full_output() {
+        // getAmountIn quoted 1001 for 2000 out, so the router may take 1001 * 1.005 rounded up
+        let (amount_in, amount_out_min) = swap_amounts(U256::from(1_001u64), U256::from(2_000u64), true, 0.5);
+        assert_eq!(amount_in, U256::from(1_007u64));
+        assert_eq!(amount_out_min, U256::from(2_000u64));
+    }
+
+    #[test]
+    fn pool_abi_quotes_exact_outputs() {
+        let function = POOL_ABI.function("getAmountIn").unwrap();
+        let inputs: Vec<String> = function.inputs.iter().map(|input| input.kind.to_string()).collect();
+        assert_eq!(inputs, vec!["address", "uint256", "address"]);
+        assert!(SyncSwap.capabilities().supports_mode(true));
+    }
+}
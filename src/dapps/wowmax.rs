@@ -0,0 +1,158 @@
+use serde_json::{json, Value};
+use std::sync::Arc;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tracing::{debug, error};
+use reqwest::Url;
+use crate::utils::utils::get_random_proxy_client;
+use crate::utils::format_swap_details::format_swap_details;
+use crate::utils::token_conversion::{format_units, parse_units};
+use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteErrorKind, QuoteRequest, QuoteResponse, RouteKind};
+
+const API_URL: &str = "https://api-gateway.wowmax.exchange";
+
+lazy_static::lazy_static! {
+    static ref CHAINS: Vec<Value> = {
+        let chains_str = include_str!("./abi/wowmax/chains.json");
+        serde_json::from_str(chains_str).expect("Failed to parse chains.json")
+    };
+}
+
+fn is_native_address(token_address: &str) -> bool {
+    let zero_address = "0x0000000000000000000000000000000000000000";
+    let eee_address = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+    let address = token_address.to_lowercase();
+    address == zero_address || address == eee_address
+}
+
+fn find_chain(chain_id: u64) -> Option<&'static Value> {
+    CHAINS.iter().find(|chain| chain["id"].as_u64() == Some(chain_id))
+}
+
+// WOWMAX routes native tokens through the chain's wrapped token
+fn resolve_token_address(token_address: &str, chain: &Value) -> Result<String, String> {
+    if is_native_address(token_address) {
+        chain["nativeCurrency"]["wrappedAddress"].as_str()
+            .map(|address| address.to_string())
+            .ok_or_else(|| "Missing wrappedAddress in WOWMAX chains.json".to_string())
+    } else {
+        Ok(token_address.to_string())
+    }
+}
+
+pub struct Wowmax;
+
+impl DappAdapter for Wowmax {
+    fn name(&self) -> &'static str {
+        "wowmax"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Swap)
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+    async move {
+        let params = &request.params;
+        debug!("Starting get_swap_quote with params: {:?}", params);
+
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let from_address = request.from_address.as_str();
+        let slippage = request.slippage;
+        let quote_only = request.quote_only;
+
+        if from_chain_id != to_chain_id {
+            return Err(QuoteError::unsupported_pair("WOWMAX only supports same-chain swaps"));
+        }
+
+        let chain = find_chain(from_chain_id)
+            .ok_or_else(|| QuoteError::unsupported_pair(format!("Chain {} is not supported by WOWMAX", from_chain_id)))?;
+        let router_address = chain["wowmaxRouter"].as_str().ok_or("Missing wowmaxRouter in WOWMAX chains.json")?;
+
+        let from_token = resolve_token_address(from_token_address, chain)?;
+        let to_token = resolve_token_address(to_token_address, chain)?;
+
+        // WOWMAX takes and returns human-readable amounts
+        let from_decimals = params["fromTokenDetails"]["decimals"].as_u64().ok_or("Missing fromTokenDetails")? as u8;
+        let to_decimals = params["toTokenDetails"]["decimals"].as_u64().ok_or("Missing toTokenDetails")? as u8;
+        let amount_raw = amount.parse::<u128>().map_err(|e| format!("Invalid amount: {}", e))?;
+        let ui_amount = format_units(amount_raw, from_decimals)?;
+
+        let client = get_random_proxy_client(&state.proxy_clients)
+            .ok_or_else(|| QuoteError::upstream("No proxy client available"))?;
+
+        let url = Url::parse(&format!("{}/chains/{}/swap", API_URL, from_chain_id)).map_err(|e| e.to_string())?;
+        let query = [
+            ("from", from_token),
+            ("to", to_token),
+            ("amount", ui_amount),
+            ("slippage", slippage.to_string()),
+            ("trader", from_address.to_string()),
+        ];
+
+        let response = client.get(url)
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| QuoteError::upstream(format!("Failed to fetch swap: {}", e)))?;
+
+        let status = response.status();
+        let result = response.json::<Value>().await
+            .map_err(|e| QuoteError::upstream(format!("Failed to parse swap response: {}", e)))?;
+
+        debug!("WOWMAX swap response: {:?}", result);
+
+        if !status.is_success() {
+            return Err(QuoteError::upstream(format!("WOWMAX API error: {}", result["message"].as_str().unwrap_or("unknown error"))));
+        }
+
+        // Never hand out calldata aimed at anything but the router we approve
+        let contract = result["contract"].as_str().ok_or("Missing contract in WOWMAX response")?;
+        if contract.to_lowercase() != router_address.to_lowercase() {
+            error!("WOWMAX returned calldata for {} instead of router {}", contract, router_address);
+            return Err(QuoteError::new(QuoteErrorKind::UnsafeCalldata, "WOWMAX calldata does not target the known router"));
+        }
+
+        let amount_out = result["amountOut"][0].as_str().ok_or("Missing amountOut in WOWMAX response")?;
+        let to_amount = parse_units(amount_out, to_decimals)?;
+
+        let transaction_data = json!({
+            "from": from_address,
+            "to": router_address,
+            "chainID": from_chain_id,
+            "data": result["data"].as_str().ok_or("Missing data in WOWMAX response")?,
+            "value": result["value"].as_str().unwrap_or("0"),
+        });
+
+        let gas_units = result["gasUnitsConsumed"].as_u64().unwrap_or(0).to_string();
+
+        let formatted_result = format_swap_details(
+            "wowmax",
+            params,
+            &transaction_data,
+            &Value::String(to_amount),
+            &Value::String(router_address.to_string()),
+            &params["gasPrices"],
+            if quote_only { Some(gas_units.as_str()) } else { None },
+            None,
+            None,
+            &state
+        ).await;
+
+        if let Err(e) = &formatted_result {
+            error!("Error formatting swap details: {}", e);
+        }
+
+        formatted_result.map_err(|e| e.context("Error formatting swap details"))
+    }.boxed()
+}
@@ -0,0 +1,188 @@
+use serde_json::{json, Value};
+use std::sync::Arc;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tracing::{debug, error};
+use reqwest::{Client, Url};
+use crate::utils::utils::get_random_proxy_client;
+use crate::utils::format_swap_details::format_swap_details;
+use crate::load_resources::AppState;
+use crate::dapps::adapter::{AdapterCapabilities, DappAdapter, QuoteError, QuoteRequest, QuoteResponse, RouteKind};
+
+const API_URL: &str = "https://aggregator-api.xy.finance/v1";
+
+lazy_static::lazy_static! {
+    static ref CHAIN_IDS: Vec<u64> = {
+        let paths_str = include_str!("./abi/xy/paths.json");
+        let paths: Value = serde_json::from_str(paths_str).expect("Failed to parse paths.json");
+        paths["supportedChains"].as_array().expect("Invalid supportedChains in paths.json")
+            .iter()
+            .filter_map(|chain| chain["chainId"].as_u64())
+            .collect()
+    };
+}
+
+pub fn supported_chain_ids() -> Vec<u64> {
+    CHAIN_IDS.clone()
+}
+
+// XY expects native tokens as the 0xeee address
+fn normalize_token_address(token_address: &str) -> String {
+    let zero_address = "0x0000000000000000000000000000000000000000";
+    let eee_address = "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE";
+    if token_address.to_lowercase() == zero_address {
+        eee_address.to_string()
+    } else {
+        token_address.to_string()
+    }
+}
+
+async fn get_xy(client: &Client, endpoint: &str, query: &[(&str, String)]) -> Result<Value, QuoteError> {
+    let url = Url::parse(&format!("{}{}", API_URL, endpoint)).map_err(|e| e.to_string())?;
+    let result = client.get(url)
+        .query(query)
+        .send()
+        .await
+        .map_err(|e| QuoteError::upstream(format!("Failed to send request to {}: {}", endpoint, e)))?
+        .json::<Value>()
+        .await
+        .map_err(|e| QuoteError::upstream(format!("Failed to parse {} response: {}", endpoint, e)))?;
+
+    // XY answers with HTTP 200 and reports failures in the body
+    if !result["success"].as_bool().unwrap_or(false) {
+        error!("XY API error on {}: {:?}", endpoint, result);
+        return Err(QuoteError::upstream(format!("XY API error: {}", result["errorMsg"].as_str().unwrap_or("unknown error"))));
+    }
+
+    Ok(result)
+}
+
+pub struct Xy;
+
+impl DappAdapter for Xy {
+    fn name(&self) -> &'static str {
+        "xy"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(RouteKind::Any).with_bundled_chains(supported_chain_ids())
+    }
+
+    fn quote(&self, request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+        get_swap_quote(request, state)
+    }
+}
+
+fn get_swap_quote(request: QuoteRequest, state: Arc<AppState>) -> BoxFuture<'static, Result<QuoteResponse, QuoteError>> {
+    async move {
+        let params = &request.params;
+        debug!("Starting get_swap_quote with params: {:?}", params);
+
+        let from_chain_id = request.from_chain_id;
+        let to_chain_id = request.to_chain_id;
+        let amount = request.amount.as_str();
+        let from_token_address = request.from_token_address.as_str();
+        let to_token_address = request.to_token_address.as_str();
+        let to_address = request.to_address.as_str();
+        let from_address = request.from_address.as_str();
+        let slippage = request.slippage;
+        let quote_only = request.quote_only;
+
+        if !CHAIN_IDS.contains(&from_chain_id) || !CHAIN_IDS.contains(&to_chain_id) {
+            return Err(QuoteError::unsupported_pair("Chain pair is not supported by XY Finance"));
+        }
+
+        let client = get_random_proxy_client(&state.proxy_clients)
+            .ok_or_else(|| QuoteError::upstream("No proxy client available"))?;
+
+        let mut query = vec![
+            ("srcChainId", from_chain_id.to_string()),
+            ("srcQuoteTokenAddress", normalize_token_address(from_token_address)),
+            ("srcQuoteTokenAmount", amount.to_string()),
+            ("dstChainId", to_chain_id.to_string()),
+            ("dstQuoteTokenAddress", normalize_token_address(to_token_address)),
+            ("slippage", slippage.to_string()),
+        ];
+
+        let quote = get_xy(&client, "/quote", &query).await?;
+        let route = quote["routes"].as_array()
+            .and_then(|routes| routes.first())
+            .ok_or_else(|| QuoteError::unsupported_pair("XY Finance returned no route for the requested pair"))?;
+
+        let contract_address = route["contractAddress"].as_str().ok_or("Missing contractAddress in XY route")?;
+        let gas_estimate = route["estimatedGas"].as_str().unwrap_or("0");
+        let dapp_options = json!({ "estimatedTime": route["estimatedTransferTime"] });
+
+        let formatted_result = if quote_only {
+            let to_amount = route["dstQuoteTokenAmount"].as_str().ok_or("Missing dstQuoteTokenAmount in XY route")?;
+
+            let quote_data = json!({
+                "from": from_address,
+                "to": contract_address,
+                "chainID": from_chain_id,
+                "data": "quote",
+                "value": "quote",
+            });
+
+            format_swap_details(
+                "xy",
+                params,
+                &quote_data,
+                &Value::String(to_amount.to_string()),
+                &Value::String(contract_address.to_string()),
+                &params["gasPrices"],
+                Some(gas_estimate),
+                None,
+                Some(&dapp_options),
+                &state
+            ).await
+        } else {
+            // buildTx needs the providers and bridge tokens the quoted route picked
+            query.push(("receiver", to_address.to_string()));
+            if let Some(provider) = route["bridgeDescription"]["provider"].as_str() {
+                query.push(("bridgeProvider", provider.to_string()));
+                query.push(("srcBridgeTokenAddress", route["bridgeDescription"]["srcBridgeTokenAddress"].as_str().unwrap_or_default().to_string()));
+                query.push(("dstBridgeTokenAddress", route["bridgeDescription"]["dstBridgeTokenAddress"].as_str().unwrap_or_default().to_string()));
+            }
+            if let Some(provider) = route["srcSwapDescription"]["provider"].as_str() {
+                query.push(("srcSwapProvider", provider.to_string()));
+            }
+            if let Some(provider) = route["dstSwapDescription"]["provider"].as_str() {
+                query.push(("dstSwapProvider", provider.to_string()));
+            }
+
+            let built = get_xy(&client, "/buildTx", &query).await?;
+            let tx = &built["tx"];
+            let to_amount = built["route"]["dstQuoteTokenAmount"].as_str()
+                .or_else(|| route["dstQuoteTokenAmount"].as_str())
+                .ok_or("Missing dstQuoteTokenAmount in XY route")?;
+
+            let transaction_data = json!({
+                "from": from_address,
+                "to": tx["to"].as_str().unwrap_or(contract_address),
+                "chainID": from_chain_id,
+                "data": tx["data"].as_str().unwrap_or("0x"),
+                "value": tx["value"].as_str().unwrap_or("0"),
+            });
+
+            format_swap_details(
+                "xy",
+                params,
+                &transaction_data,
+                &Value::String(to_amount.to_string()),
+                &Value::String(contract_address.to_string()),
+                &params["gasPrices"],
+                None,
+                None,
+                Some(&dapp_options),
+                &state
+            ).await
+        };
+
+        if let Err(e) = &formatted_result {
+            error!("Error formatting swap details: {}", e);
+        }
+
+        formatted_result.map_err(|e| e.context("Error formatting swap details"))
+    }.boxed()
+}
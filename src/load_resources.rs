@@ -10,6 +10,7 @@ use crate::create_clients::{
 };
 use std::collections::HashMap;
 use crate::utils::utils::{precompute_proxy_clients, precompute_chain_providers};
+use crate::utils::gas_cache::GasCache;
 
 pub struct AppState {
     pub dapps: Value,
@@ -23,6 +24,7 @@ pub struct AppState {
     //pub web3_rpc_proxy_providers: Web3RpcProxyProviderMap,
     pub jsonrpc_rpc_proxy_providers: JsonRpcProxyProviderMap,
     pub quote_cache: Arc<DashMap<String, Value>>,
+    pub gas_cache: GasCache,
 }
 
 // Function to load JSON from a file
@@ -82,6 +84,10 @@ pub async fn create_app_state() -> AppState {
     //tracing::info!("Loaded {} web3 RPC proxy providers", precomputed_web3_providers.len());
     tracing::info!("Loaded {} JSON-RPC proxy providers", precomputed_jsonrpc_providers.len());
 
+    // Index bundled dapp paths up front instead of on the first quote
+    crate::dapps::owlto::load_pairs();
+    crate::dapps::load_adapters();
+
     AppState {
         dapps,
         chains,
@@ -94,6 +100,7 @@ pub async fn create_app_state() -> AppState {
         //web3_rpc_proxy_providers: precomputed_web3_providers,
         jsonrpc_rpc_proxy_providers: precomputed_jsonrpc_providers,
         quote_cache,
+        gas_cache: Arc::new(DashMap::new()),
     }
 }
 
@@ -10,6 +10,7 @@ use axum::{Server, Router, extract::Extension};
 use axum::http::Request;
 use api::create_api_routes;
 use load_resources::{create_app_state, reload_tokens};
+use utils::gas_cache::refresh_gas_cache;
 use path_updater::start_all_update_processes;
 use std::env;
 use std::fs::File;
@@ -53,6 +54,12 @@ async fn main() {
         reload_tokens(state_clone).await;
     });
 
+    // Spawn a background task to keep gas prices warm for chains being quoted
+    let state_clone = Arc::clone(&state);
+    task::spawn(async move {
+        refresh_gas_cache(state_clone).await;
+    });
+
     /*/ Spawn a background task to start the update processes without blocking the main API
     let state_clone = Arc::clone(&state);
     task::spawn(async move {
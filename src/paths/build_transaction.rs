@@ -0,0 +1,138 @@
+//src/paths/build_transaction.rs
+use axum::{Json, Router, routing::post, extract::Extension, http::StatusCode};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tracing::error;
+use crate::services::quote_service::process_transaction_from_quote;
+use crate::load_resources::AppState;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildTransactionParams {
+    pub request_id: String,
+    // The `id` of the chosen quote within the cached response
+    #[serde(deserialize_with = "crate::paths::utils::deserialization_helpers::string_or_number_to_u32")]
+    pub id: u32,
+    // Signed permit ({kind, signature, deadline}) answering an earlier permitRequired response
+    pub permit: Option<Value>,
+}
+
+fn failure(status: StatusCode, message: &str) -> (StatusCode, Json<Value>) {
+    (status, Json(json!({
+        "success": false,
+        "message": message
+    })))
+}
+
+pub async fn post_build_transaction_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Json(params): Json<BuildTransactionParams>,
+) -> (StatusCode, Json<Value>) {
+    tracing::info!("Received POST /api/build-transaction request: {:?}", params);
+
+    // Cached responses only ever disappear by expiring, so a missing requestId is treated as gone
+    let quote = {
+        let Some(cached) = state.quote_cache.get(&params.request_id) else {
+            return failure(StatusCode::GONE, "Quote has expired, request a new quote");
+        };
+        cached["data"].as_array()
+            .and_then(|quotes| quotes.iter().find(|quote| quote["id"].as_u64() == Some(params.id as u64)))
+            .cloned()
+    };
+
+    let Some(quote) = quote else {
+        return failure(StatusCode::NOT_FOUND, "Quote not found for this requestId");
+    };
+
+    // Firm quotes leave a tombstone once the expiry task has dropped them
+    if quote["expired"].as_bool().unwrap_or(false) {
+        return failure(StatusCode::GONE, "Quote has expired, request a new quote");
+    }
+    let quote = &quote["data"];
+
+    // The expiry task may not have run yet
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    if quote["expiresAt"].as_u64().is_some_and(|expires_at| expires_at <= now) {
+        return failure(StatusCode::GONE, "Quote has expired, request a new quote");
+    }
+
+    match process_transaction_from_quote(quote, params.permit.as_ref(), &state).await {
+        Ok(transaction) => (StatusCode::OK, Json(json!({
+            "success": true,
+            "requestId": params.request_id,
+            "id": params.id,
+            "data": transaction
+        }))),
+        Err(e) => {
+            error!("Error building transaction: {}", e);
+            failure(StatusCode::OK, &format!("Server error: {}", e))
+        }
+    }
+}
+
+pub fn create_build_transaction_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/api/build-transaction", post(post_build_transaction_handler))
+        .layer(Extension(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::quote_service::expire_quote;
+    use dashmap::DashMap;
+
+    fn state_with_cached(request_id: &str, response: Value) -> Arc<AppState> {
+        let quote_cache = Arc::new(DashMap::new());
+        quote_cache.insert(request_id.to_string(), response);
+        Arc::new(AppState {
+            dapps: Value::Null,
+            chains: Value::Null,
+            tokens: Arc::new(DashMap::new()),
+            dapp_config: Value::Null,
+            rpc_config: Value::Null,
+            settings: Value::Null,
+            proxy_clients: Arc::new(DashMap::new()),
+            rpc_proxy_providers: Arc::new(DashMap::new()),
+            jsonrpc_rpc_proxy_providers: Arc::new(DashMap::new()),
+            quote_cache,
+            gas_cache: Arc::new(DashMap::new()),
+        })
+    }
+
+    async fn build(state: &Arc<AppState>, request_id: &str, id: u32) -> StatusCode {
+        let params = BuildTransactionParams { request_id: request_id.to_string(), id, permit: None };
+        post_build_transaction_handler(Extension(Arc::clone(state)), Json(params)).await.0
+    }
+
+    fn firm_quote_response(expires_at: u64) -> Value {
+        json!({ "data": [{ "id": 0, "data": { "tool": "native", "expiresAt": expires_at } }] })
+    }
+
+    #[tokio::test]
+    async fn dropped_firm_quote_is_gone() {
+        let mut response = firm_quote_response(1);
+        expire_quote(&mut response, 0);
+        let state = state_with_cached("request", response);
+
+        assert_eq!(build(&state, "request", 0).await, StatusCode::GONE);
+    }
+
+    #[tokio::test]
+    async fn lapsed_quote_not_yet_dropped_is_gone() {
+        let state = state_with_cached("request", firm_quote_response(1));
+        assert_eq!(build(&state, "request", 0).await, StatusCode::GONE);
+    }
+
+    #[tokio::test]
+    async fn quote_that_never_existed_is_not_found() {
+        let mut response = firm_quote_response(1);
+        expire_quote(&mut response, 0);
+        let state = state_with_cached("request", response);
+
+        assert_eq!(build(&state, "request", 7).await, StatusCode::NOT_FOUND);
+        assert_eq!(build(&state, "unknown", 0).await, StatusCode::GONE);
+    }
+}
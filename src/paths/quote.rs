@@ -16,8 +16,11 @@ pub struct QuoteParams {
     #[serde(deserialize_with = "crate::paths::utils::deserialization_helpers::string_or_number_to_u32")]
     pub from_chain_id: u32,
     pub from_address: String,
-    #[serde(deserialize_with = "crate::paths::utils::deserialization_helpers::number_to_string")]
+    // Exact input amount, left empty when quoting an exact output with `to_amount`
+    #[serde(deserialize_with = "crate::paths::utils::deserialization_helpers::number_to_string", default)]
     pub amount: String,
+    #[serde(deserialize_with = "crate::paths::utils::deserialization_helpers::number_to_option_string", default)]
+    pub to_amount: Option<String>,
     pub from_token_address: String,
     pub to_token_address: String,
     pub to_address: Option<String>,
@@ -36,8 +39,11 @@ pub struct GetQuoteParams {
     #[serde(deserialize_with = "crate::paths::utils::deserialization_helpers::string_or_number_to_u32")]
     pub from_chain_id: u32,
     pub from_address: String,
-    #[serde(deserialize_with = "crate::paths::utils::deserialization_helpers::number_to_string")]
+    // Exact input amount, left empty when quoting an exact output with `to_amount`
+    #[serde(deserialize_with = "crate::paths::utils::deserialization_helpers::number_to_string", default)]
     pub amount: String,
+    #[serde(deserialize_with = "crate::paths::utils::deserialization_helpers::number_to_option_string", default)]
+    pub to_amount: Option<String>,
     pub from_token_address: String,
     pub to_token_address: String,
     pub to_address: Option<String>,
@@ -53,6 +59,28 @@ pub struct GetQuoteParams {
 }
 
 
+// GET requests pass slippage and dapps as top-level query parameters
+impl From<GetQuoteParams> for QuoteParams {
+    fn from(params: GetQuoteParams) -> Self {
+        QuoteParams {
+            from_chain_id: params.from_chain_id,
+            from_address: params.from_address,
+            amount: params.amount,
+            to_amount: params.to_amount,
+            from_token_address: params.from_token_address,
+            to_token_address: params.to_token_address,
+            to_address: params.to_address,
+            to_chain_id: params.to_chain_id,
+            dapps: params.dapps.clone(),  // Pass dapps directly
+            options: Some(serde_json::json!({
+                "slippage": params.slippage,  // Pass slippage under options
+                "dapps": params.dapps,  // Also pass dapps under options
+            })),
+            other_params: params.other_params,
+        }
+    }
+}
+
 // Custom deserializer for comma-separated dapps
 pub fn comma_separated<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
@@ -64,20 +92,12 @@ where
         .collect())
 }
 
-async fn handle_quote_request(
-    Extension(state): Extension<Arc<AppState>>, 
-    params: QuoteParams,
-    is_post: bool,
-) -> Result<Json<Value>, StatusCode> {
-    tracing::info!("Handling request for /api/quote");
-
-    let validation = validate_required_params(&params, &state);
+// Validates the request and builds the params the quote services expect
+pub fn build_quote_params(params: QuoteParams, is_post: bool, state: &AppState) -> Result<Value, String> {
+    let validation = validate_required_params(&params, state);
     if !validation.valid {
         error!("Validation failed: {}", validation.message);
-        return Ok(Json(serde_json::json!({
-            "success": false,
-            "message": validation.message
-        })));
+        return Err(validation.message);
     }
 
     let to_address = params.to_address.clone().unwrap_or(params.from_address.clone());
@@ -93,6 +113,7 @@ async fn handle_quote_request(
         "fromChainId": params.from_chain_id,
         "fromAddress": params.from_address,
         "amount": params.amount,
+        "toAmount": params.to_amount,
         "fromTokenAddress": params.from_token_address,
         "toTokenAddress": params.to_token_address,
         "toAddress": to_address,
@@ -105,6 +126,26 @@ async fn handle_quote_request(
 
     tracing::debug!("Transaction params: {:?}", transaction_params);
 
+    Ok(transaction_params)
+}
+
+async fn handle_quote_request(
+    Extension(state): Extension<Arc<AppState>>, 
+    params: QuoteParams,
+    is_post: bool,
+) -> Result<Json<Value>, StatusCode> {
+    tracing::info!("Handling request for /api/quote");
+
+    let transaction_params = match build_quote_params(params, is_post, &state) {
+        Ok(transaction_params) => transaction_params,
+        Err(message) => {
+            return Ok(Json(serde_json::json!({
+                "success": false,
+                "message": message
+            })));
+        }
+    };
+
     match process_quote(transaction_params, Arc::clone(&state.tokens), Arc::clone(&state)).await {
         Ok(response) => {
             tracing::debug!("Quote processed successfully: {:?}", response);
@@ -134,24 +175,7 @@ pub async fn get_quote_handler(
 ) -> Result<Json<Value>, StatusCode> {
     tracing::info!("Received GET /api/quote request");
 
-    // Convert GetQuoteParams to QuoteParams before passing to handle_quote_request
-    let params = QuoteParams {
-        from_chain_id: params.from_chain_id,
-        from_address: params.from_address,
-        amount: params.amount,
-        from_token_address: params.from_token_address,
-        to_token_address: params.to_token_address,
-        to_address: params.to_address,
-        to_chain_id: params.to_chain_id,
-        dapps: params.dapps.clone(),  // Pass dapps directly
-        options: Some(serde_json::json!({
-            "slippage": params.slippage,  // Pass slippage under options
-            "dapps": params.dapps,  // Also pass dapps under options
-        })),
-        other_params: params.other_params,
-    };
-
-    handle_quote_request(Extension(state), params, false).await
+    handle_quote_request(Extension(state), params.into(), false).await
 }
 
 
@@ -0,0 +1,58 @@
+//src/paths/quote_stream.rs
+use axum::{Router, routing::get, extract::Query, extract::Extension};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures::stream::{self, Stream, StreamExt};
+use std::convert::Infallible;
+use std::sync::Arc;
+use serde_json::{Value, json};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::error;
+use crate::services::quote_service::process_quote_stream;
+use crate::load_resources::AppState;
+use crate::paths::quote::{build_quote_params, GetQuoteParams};
+
+// Each stream item names its SSE event ("quote", "error" or "summary") and carries the payload
+fn to_event(result: Result<Value, String>) -> Event {
+    let (name, data) = match result {
+        Ok(event) => (event["event"].as_str().unwrap_or("quote").to_string(), event["data"].clone()),
+        Err(message) => {
+            error!("Error processing quote stream: {}", message);
+            ("summary".to_string(), json!({
+                "success": false,
+                "message": format!("Server error: {}", message)
+            }))
+        }
+    };
+
+    Event::default()
+        .event(name)
+        .json_data(&data)
+        .unwrap_or_else(|e| Event::default().event("summary").data(format!("{{\"success\":false,\"message\":\"{}\"}}", e)))
+}
+
+pub async fn get_quote_stream_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Query(params): Query<GetQuoteParams>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    tracing::info!("Received GET /api/quote/stream request");
+
+    // Validation failures end the stream with a single failed summary
+    let events = match build_quote_params(params.into(), false, &state) {
+        Ok(transaction_params) => {
+            let receiver = process_quote_stream(transaction_params, Arc::clone(&state)).await;
+            ReceiverStream::new(receiver).map(to_event).left_stream()
+        }
+        Err(message) => {
+            let summary = json!({ "event": "summary", "data": { "success": false, "message": message } });
+            stream::once(async move { to_event(Ok(summary)) }).right_stream()
+        }
+    };
+
+    Sse::new(events.map(Ok)).keep_alive(KeepAlive::default())
+}
+
+pub fn create_quote_stream_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/api/quote/stream", get(get_quote_stream_handler))
+        .layer(Extension(state))
+}
@@ -37,6 +37,23 @@ where
     }
 }
 
+// Custom deserializer for optional amounts such as `toAmount`
+pub fn number_to_option_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+
+    debug!("Deserializing value for Option<String>: {:?}", value);
+
+    match value {
+        serde_json::Value::Number(num) => Ok(Some(num.to_string())),
+        serde_json::Value::String(s) => Ok(Some(s)),
+        serde_json::Value::Null => Ok(None),
+        _ => Err(de::Error::custom("Expected a string, number, or null")),
+    }
+}
+
 // Custom deserializer to handle both number and string inputs for Option<u32>
 pub fn string_or_number_to_option_u32<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
 where
@@ -2,8 +2,10 @@
 use std::collections::HashMap;
 use serde_json::Value;
 use regex::Regex;
+use ethers::types::U256;
 use crate::load_resources::AppState;
 use crate::paths::quote::QuoteParams;
+use crate::utils::utils::{BITCOIN_CHAIN_ID, is_bitcoin_address};
 
 pub struct ValidationResult {
     pub valid: bool,
@@ -14,7 +16,6 @@ pub fn validate_required_params(params: &QuoteParams, state: &AppState) -> Valid
     let mandatory_params = vec![
         "fromChainId",
         "fromAddress",
-        "amount",
         "fromTokenAddress",
         "toTokenAddress",
     ];
@@ -23,7 +24,6 @@ pub fn validate_required_params(params: &QuoteParams, state: &AppState) -> Valid
         if match *param {
             "fromChainId" if params.from_chain_id == 0 => true,
             "fromAddress" if params.from_address.is_empty() => true,
-            "amount" if params.amount.is_empty() => true,
             "fromTokenAddress" if params.from_token_address.is_empty() => true,
             "toTokenAddress" if params.to_token_address.is_empty() => true,
             _ => false,
@@ -35,6 +35,32 @@ pub fn validate_required_params(params: &QuoteParams, state: &AppState) -> Valid
         }
     }
 
+    // Quotes are either exact input (amount) or exact output (toAmount)
+    let to_amount = params.to_amount.as_deref().filter(|a| !a.is_empty());
+    let quoted_amount = match (params.amount.is_empty(), to_amount) {
+        (true, None) => {
+            return ValidationResult {
+                valid: false,
+                message: "Missing mandatory parameter: amount or toAmount".to_string(),
+            };
+        }
+        (false, Some(_)) => {
+            return ValidationResult {
+                valid: false,
+                message: "Provide either amount or toAmount, not both".to_string(),
+            };
+        }
+        (false, None) => ("amount", params.amount.as_str()),
+        (true, Some(to_amount)) => ("toAmount", to_amount),
+    };
+
+    if U256::from_dec_str(quoted_amount.1).map_or(true, |amount| amount.is_zero()) {
+        return ValidationResult {
+            valid: false,
+            message: format!("Invalid {}", quoted_amount.0),
+        };
+    }
+
     let chains = state.chains["chains"].as_array().unwrap();
     let is_valid_chain_id = |id: u32| chains.iter().any(|chain| {
         chain["id"].as_u64().map(|v| v == id as u64).unwrap_or(false)
@@ -47,8 +73,10 @@ pub fn validate_required_params(params: &QuoteParams, state: &AppState) -> Valid
         };
     }
 
+    let is_bitcoin_destination = params.to_chain_id.map(|id| id as u64) == Some(BITCOIN_CHAIN_ID);
+
     if let Some(to_chain_id) = params.to_chain_id {
-        if !is_valid_chain_id(to_chain_id) {
+        if !is_bitcoin_destination && !is_valid_chain_id(to_chain_id) {
             return ValidationResult {
                 valid: false,
                 message: "Invalid toChainId".to_string(),
@@ -67,6 +95,26 @@ pub fn validate_required_params(params: &QuoteParams, state: &AppState) -> Valid
         };
     }
 
+    // Bitcoin destinations need an explicit BTC recipient, EVM ones a 0x address
+    if is_bitcoin_destination {
+        match &params.to_address {
+            Some(to_address) if is_bitcoin_address(to_address) => {}
+            _ => {
+                return ValidationResult {
+                    valid: false,
+                    message: "Invalid toAddress for Bitcoin destination".to_string(),
+                };
+            }
+        }
+    } else if let Some(to_address) = &params.to_address {
+        if !address_regex.is_match(to_address) {
+            return ValidationResult {
+                valid: false,
+                message: "Invalid address format".to_string(),
+            };
+        }
+    }
+
     ValidationResult {
         valid: true,
         message: "Valid parameters".to_string(),
@@ -1,129 +1,177 @@
-use ethers::abi::{Function, Param, ParamType};
+//src/services/approval.rs
+use ethers::abi::{Function, Param, ParamType, StateMutability, Token};
 use ethers::prelude::*;
-use ethers::types::{TransactionRequest, U256, Address, Bytes, U64};
+use ethers::types::{Eip1559TransactionRequest, U256, Address, Bytes};
 use ethers::types::transaction::eip2718::TypedTransaction;
-use serde_json::Value;
+use serde_json::{Value, json};
 use std::sync::Arc;
 use std::str::FromStr;
 use crate::load_resources::AppState;
-use crate::utils::get_random_rpc_proxy_provider;
-use tracing::debug;
+use crate::utils::allowance_checker::get_user_allowance;
+use crate::utils::utils::get_random_rpc_proxy_provider;
+use tracing::{debug, error};
 
-pub async fn generate_approval_transaction(
-    quote: &Value,
-    state: Arc<AppState>,
-) -> Result<Option<TypedTransaction>, Box<dyn std::error::Error>> {
-    let approval_address = quote["approvalAddress"].as_str().unwrap();
-    let from_token = &quote["fromToken"];
-    let transaction = &quote["transaction"];
-    let approve = quote["approve"].as_str().unwrap_or("max");
-    let from_amount = U256::from_dec_str(quote["fromAmount"].as_str().unwrap()).unwrap();
-    let chain_id = from_token["chainId"].as_u64().unwrap();
-
-    let provider = get_random_rpc_proxy_provider(chain_id, &state.rpc_proxy_providers)
-        .ok_or("No provider available")?;
+// Used when approve can't be estimated, e.g. USDT before its allowance reset is mined
+const APPROVE_GAS_FALLBACK: u64 = 100_000;
 
-    let max_value = U256::MAX;
-    let approval_amount = match approve {
-        "max" => max_value,
-        "value" => from_amount,
-        _ => max_value,
-    };
+// Tokens that revert on approve unless the current allowance is zero, by chain
+const RESET_BEFORE_APPROVE: &[(u64, &str)] = &[
+    (1, "0xdac17f958d2ee523a2206206994597c13d831ec7"), // USDT
+    (1, "0xdd974d5c2e2928dea5f71b9825b8b646686bd200"), // KNC (legacy)
+    (1, "0x0d8775f648430679a709e98d2b0cb6250d2887ef"), // BAT
+];
 
-    let from_token_address = from_token["address"].as_str().unwrap();
-    if from_token_address == "0x0000000000000000000000000000000000000000" {
-        tracing::info!("fromToken.address is zero address, returning 'none'");
-        return Ok(None);
-    }
-
-    // Define the allowance function
-    let allowance_function = Function {
-        name: "allowance".to_owned(),
-        inputs: vec![
-            Param {
-                name: "owner".to_owned(),
-                kind: ParamType::Address,
-            },
-            Param {
-                name: "spender".to_owned(),
-                kind: ParamType::Address,
-            },
-        ],
-        outputs: vec![Param {
-            name: "remaining".to_owned(),
-            kind: ParamType::Uint(256),
-        }],
-        constant: Some(true),
-        state_mutability: StateMutability::View,
-    };
-
-    let owner = transaction["from"].as_str().unwrap().parse::<Address>()?;
-    let spender = approval_address.parse::<Address>()?;
-
-    let allowance_data = allowance_function.encode_input(&[
-        owner.into(),
-        spender.into(),
-    ])?;
-
-    let call = provider
-        .call(&TypedTransaction::Legacy(TransactionRequest {
-            to: Some(from_token_address.parse()?),
-            data: Some(Bytes::from(allowance_data)),
-            ..Default::default()
-        }), None)
-        .await?;
-
-    let allowance_amount = U256::from_big_endian(&call);
-    if allowance_amount >= from_amount {
-        tracing::info!("Allowance is sufficient, returning 'none'");
-        return Ok(None);
-    }
+fn needs_reset_before_approve(chain_id: u64, token_address: &str) -> bool {
+    let token_address = token_address.to_lowercase();
+    RESET_BEFORE_APPROVE.iter().any(|(chain, token)| *chain == chain_id && *token == token_address)
+}
 
-    // Define the approve function
-    let approve_function = Function {
+fn approve_function() -> Function {
+    #[allow(deprecated)]
+    Function {
         name: "approve".to_owned(),
         inputs: vec![
             Param {
                 name: "spender".to_owned(),
                 kind: ParamType::Address,
+                internal_type: None,
             },
             Param {
                 name: "amount".to_owned(),
                 kind: ParamType::Uint(256),
+                internal_type: None,
             },
         ],
         outputs: vec![],
         constant: None,
         state_mutability: StateMutability::NonPayable,
-    };
+    }
+}
 
-    let approve_data = approve_function.encode_input(&[
-        spender.into(),
-        approval_amount.into(),
-    ])?;
-
-    // Create the approval transaction
-    let tx_request = TransactionRequest {
-        from: Some(owner),
-        to: Some(from_token_address.parse()?),
-        data: Some(Bytes::from(approve_data)),
-        value: Some(U256::zero()),
-        gas_price: Some(U256::from_dec_str(transaction["gasPrice"].as_str().unwrap())?),
-        chain_id: Some(chain_id.into()),
-        ..Default::default()
-    };
+// Builds an EIP-1559 approve(spender, amount) transaction, estimating its gas where possible
+async fn build_approve_transaction(
+    provider: &Arc<Provider<Http>>,
+    chain_id: u64,
+    owner: Address,
+    token: Address,
+    spender: Address,
+    amount: U256,
+    fees: (U256, U256),
+) -> Result<Value, String> {
+    let approve_data = approve_function()
+        .encode_input(&[Token::Address(spender), Token::Uint(amount)])
+        .map_err(|e| format!("Failed to encode approve: {}", e))?;
 
-    let typed_tx = TypedTransaction::Legacy(tx_request);
+    let (max_fee_per_gas, max_priority_fee_per_gas) = fees;
+    let tx_request = Eip1559TransactionRequest::new()
+        .from(owner)
+        .to(token)
+        .data(Bytes::from(approve_data))
+        .value(U256::zero())
+        .chain_id(chain_id)
+        .max_fee_per_gas(max_fee_per_gas)
+        .max_priority_fee_per_gas(max_priority_fee_per_gas);
 
-    // Estimate gas
-    match provider.estimate_gas(&typed_tx, None).await {
-        Ok(gas) => Ok(Some(TypedTransaction::Legacy(TransactionRequest {
-            gas: Some(gas),
-            ..tx_request
-        }))),
+    let gas = match provider.estimate_gas(&TypedTransaction::Eip1559(tx_request.clone()), None).await {
+        Ok(gas) => gas * 6 / 5,
         Err(e) => {
-            tracing::error!("Error estimating gas: {}", e);
-            Ok(None)
+            debug!("Approve gas estimation failed, using fallback: {}", e);
+            U256::from(APPROVE_GAS_FALLBACK)
         }
+    };
+
+    Ok(json!({
+        "type": 2,
+        "from": format!("{:?}", owner),
+        "to": format!("{:?}", token),
+        "data": tx_request.data.map(|d| d.to_string()).unwrap_or_default(),
+        "value": "0",
+        "chainId": chain_id,
+        "gas": gas.to_string(),
+        "maxFeePerGas": max_fee_per_gas.to_string(),
+        "maxPriorityFeePerGas": max_priority_fee_per_gas.to_string()
+    }))
+}
+
+// The allowance a quote needs, exact-output swaps may pull up to fromAmountMax
+pub fn required_allowance(quote: &Value) -> Result<U256, String> {
+    let required_amount = quote["fromAmountMax"].as_str()
+        .or_else(|| quote["fromAmount"].as_str())
+        .ok_or("Missing fromAmount")?;
+    U256::from_dec_str(required_amount).map_err(|e| format!("Invalid fromAmount: {}", e))
+}
+
+// Returns the approvals a built quote needs before it can execute, or None when the allowance suffices
+pub async fn generate_approval_transaction(
+    quote: &Value,
+    state: &Arc<AppState>,
+) -> Result<Option<Value>, String> {
+    let approval_address = quote["approvalAddress"].as_str().unwrap_or("none");
+    if approval_address == "none" {
+        return Ok(None);
+    }
+
+    let Some(allowance) = get_user_allowance(quote, state).await? else {
+        return Ok(None);
+    };
+
+    let required_amount = required_allowance(quote)?;
+
+    if allowance >= required_amount {
+        debug!("Allowance {} covers {}, no approval needed", allowance, required_amount);
+        return Ok(None);
     }
+
+    let approve = quote["options"]["approve"].as_str()
+        .or_else(|| quote["approve"].as_str())
+        .unwrap_or("max");
+    let approval_amount = match approve {
+        "value" => required_amount,
+        _ => U256::MAX,
+    };
+
+    let chain_id = quote["fromChainId"].as_u64().ok_or("Invalid fromChainId")?;
+    let token_address = quote["fromToken"]["address"].as_str().ok_or("Missing fromToken address")?;
+    let owner = Address::from_str(quote["fromAddress"].as_str().ok_or("Missing fromAddress")?)
+        .map_err(|e| format!("Invalid from address: {}", e))?;
+    let token = Address::from_str(token_address).map_err(|e| format!("Invalid token address: {}", e))?;
+    let spender = Address::from_str(approval_address).map_err(|e| format!("Invalid approval address: {}", e))?;
+
+    let provider = get_random_rpc_proxy_provider(chain_id, &state.rpc_proxy_providers)
+        .ok_or_else(|| format!("No RPC provider found for chain ID: {}", chain_id))?;
+
+    // The swap's normal-tier fees are reused when the fee oracle found them
+    let quoted_fees = quote["transaction"]["maxFeePerGas"].as_str()
+        .zip(quote["transaction"]["maxPriorityFeePerGas"].as_str())
+        .and_then(|(max_fee, priority_fee)| Some((U256::from_dec_str(max_fee).ok()?, U256::from_dec_str(priority_fee).ok()?)));
+
+    // Chains without a base fee fall back to the quote's gas price for both fields
+    let fees = match quoted_fees {
+        Some(fees) => fees,
+        None => match provider.estimate_eip1559_fees(None).await {
+            Ok(fees) => fees,
+            Err(e) => {
+                error!("Failed to estimate EIP-1559 fees on chain {}: {}", chain_id, e);
+                let gas_price = U256::from_dec_str(quote["transaction"]["gasPrice"].as_str().unwrap_or("0")).unwrap_or_default();
+                (gas_price, gas_price)
+            }
+        },
+    };
+
+    let approval_transaction = build_approve_transaction(&provider, chain_id, owner, token, spender, approval_amount, fees).await?;
+
+    let mut approval = json!({
+        "approve": approve,
+        "amount": approval_amount.to_string(),
+        "currentAllowance": allowance.to_string(),
+        "transaction": approval_transaction
+    });
+
+    // USDT-style tokens only accept a new allowance once the old one has been set to zero
+    if !allowance.is_zero() && needs_reset_before_approve(chain_id, token_address) {
+        let reset_transaction = build_approve_transaction(&provider, chain_id, owner, token, spender, U256::zero(), fees).await?;
+        approval["resetTransaction"] = reset_transaction;
+    }
+
+    Ok(Some(approval))
 }
@@ -0,0 +1,438 @@
+//src/services/calldata_verifier.rs
+use ethers::abi::{decode, Function, HumanReadableParser, ParamType, Token};
+use ethers::types::{Address, U256};
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use tracing::{debug, error};
+
+// Contracts each API-driven dapp may send users to or ask them to approve, by chain.
+// A quote from these dapps pointing anywhere else is rejected, so a new chain needs its router listed here first
+const ROUTER_ALLOWLIST: &str = r#"{
+    "jumper": [
+        {"address": "0x1231DEB6f5749EF6cE6943a275A1D3E7486F4EaE", "chains": [1, 10, 56, 100, 137, 250, 252, 1101, 1284, 1285, 5000, 8453, 34443, 42161, 42220, 43114, 59144, 81457, 534352, 1313161554]},
+        {"address": "0x341e94069f53234fE6DabeF707aD424830525715", "chains": [324]}
+    ],
+    "bungee": [
+        {"address": "0x3a23F943181408EAC424116Af7b7790c94Cb97a5", "chains": [1, 10, 56, 100, 137, 250, 1101, 5000, 8453, 34443, 42161, 43114, 59144, 81457, 534352, 1313161554]},
+        {"address": "0xaDdE7028e7ec226777e5dea5D53F6457C21ec7D6", "chains": [324]}
+    ],
+    "debridge": [
+        {"address": "0xeF4fB24aD0916217251F553c0596F8Edc630EB66", "chains": [1, 10, 56, 100, 137, 8453, 42161, 43114, 59144]},
+        {"address": "0x663DC15D3C1aC63ff12E45Ab68FeA3F0a883C251", "chains": [1, 10, 56, 100, 137, 8453, 42161, 43114, 59144]}
+    ],
+    "balancer": [
+        {"address": "0xBA12222222228d8Ba445958a75a0704d566BF2C8", "chains": [1, 10, 100, 137, 252, 1101, 8453, 34443, 42161, 43114]}
+    ]
+}"#;
+
+// API-driven dapps whose routers aren't listed yet; their transactions still go out, flagged as unverified
+const UNLISTED_API_DAPPS: &[&str] = &["odos", "xy", "native", "layerswap", "symbiosis", "synapse", "owlto", "wowmax"];
+
+// Calls whose receiver and input amount we know how to read, per dapp.
+// Bungee has none: its gateway dispatches on a route id to a separate implementation per bridge, so its quotes only get the router check
+const KNOWN_FUNCTIONS: &[(&str, &str)] = &[
+    ("jumper", "function swapTokensGeneric(bytes32,string,string,address,uint256,(address,address,address,address,uint256,bytes,bool)[])"),
+    ("jumper", "function swapTokensSingleV3ERC20ToERC20(bytes32,string,string,address,uint256,(address,address,address,address,uint256,bytes,bool))"),
+    ("jumper", "function swapTokensSingleV3ERC20ToNative(bytes32,string,string,address,uint256,(address,address,address,address,uint256,bytes,bool))"),
+    ("jumper", "function swapTokensSingleV3NativeToERC20(bytes32,string,string,address,uint256,(address,address,address,address,uint256,bytes,bool))"),
+    ("jumper", "function swapTokensMultipleV3ERC20ToERC20(bytes32,string,string,address,uint256,(address,address,address,address,uint256,bytes,bool)[])"),
+    ("jumper", "function swapTokensMultipleV3ERC20ToNative(bytes32,string,string,address,uint256,(address,address,address,address,uint256,bytes,bool)[])"),
+    ("jumper", "function swapTokensMultipleV3NativeToERC20(bytes32,string,string,address,uint256,(address,address,address,address,uint256,bytes,bool)[])"),
+    ("debridge", "function createOrder((address,uint256,bytes,uint256,uint256,bytes,address,bytes,bytes,bytes,bytes),bytes,uint32,bytes)"),
+    ("debridge", "function createSaltedOrder((address,uint256,bytes,uint256,uint256,bytes,address,bytes,bytes,bytes,bytes),uint64,bytes,uint32,bytes,bytes)"),
+    ("balancer", "function swap((bytes32,uint8,address,address,uint256,bytes),(address,bool,address,bool),uint256,uint256)"),
+    ("balancer", "function batchSwap(uint8,(bytes32,uint256,uint256,uint256,bytes)[],address[],(address,bool,address,bool),int256[],uint256)"),
+];
+
+// LI.FI's receiver placeholder for non-EVM destinations
+const LIFI_NON_EVM_RECEIVER: &str = "0x11f111f111f111F111f111f111F111f111f111F1";
+
+lazy_static::lazy_static! {
+    static ref ALLOWLIST: HashMap<String, HashMap<u64, HashSet<Address>>> = {
+        let entries: HashMap<String, Vec<Value>> = serde_json::from_str(ROUTER_ALLOWLIST).expect("Failed to parse router allowlist");
+        entries.into_iter().map(|(dapp, routers)| {
+            let mut by_chain: HashMap<u64, HashSet<Address>> = HashMap::new();
+            for router in routers {
+                let address = Address::from_str(router["address"].as_str().expect("Missing router address"))
+                    .expect("Invalid router address");
+                for chain_id in router["chains"].as_array().expect("Missing router chains") {
+                    by_chain.entry(chain_id.as_u64().expect("Invalid chain ID")).or_default().insert(address);
+                }
+            }
+            (dapp, by_chain)
+        }).collect()
+    };
+    static ref FUNCTIONS: Vec<(&'static str, Function)> = KNOWN_FUNCTIONS.iter()
+        .map(|(dapp, signature)| (*dapp, HumanReadableParser::parse_function(signature).expect("Failed to parse known function")))
+        .collect();
+    // The BridgeData struct every LI.FI bridge facet takes first, followed by the source swaps when it has any
+    static ref LIFI_BRIDGE_DATA: ParamType = ParamType::Tuple(vec![
+        ParamType::FixedBytes(32), ParamType::String, ParamType::String, ParamType::Address, ParamType::Address,
+        ParamType::Address, ParamType::Uint(256), ParamType::Uint(256), ParamType::Bool, ParamType::Bool,
+    ]);
+    static ref LIFI_SWAP_DATA: ParamType = ParamType::Array(Box::new(ParamType::Tuple(vec![
+        ParamType::Address, ParamType::Address, ParamType::Address, ParamType::Address,
+        ParamType::Uint(256), ParamType::Bytes, ParamType::Bool,
+    ])));
+}
+
+// What a decoded call does with the user's funds
+struct DecodedCall {
+    function: String,
+    // Raw receiver bytes, None when the call hands off to a destination call instead
+    receiver: Option<Vec<u8>>,
+    // The most the call can pull from the user
+    amount_in: U256,
+}
+
+fn tuple_field(token: &Token, index: usize) -> Option<&Token> {
+    match token {
+        Token::Tuple(fields) => fields.get(index),
+        _ => None,
+    }
+}
+
+fn array_item(token: &Token, index: usize) -> Option<&Token> {
+    match token {
+        Token::Array(items) => items.get(index),
+        _ => None,
+    }
+}
+
+fn address_bytes(token: &Token) -> Option<Vec<u8>> {
+    token.clone().into_address().map(|address| address.as_bytes().to_vec())
+}
+
+fn decode_known_call(function: &Function, tokens: &[Token]) -> Option<DecodedCall> {
+    let (receiver, amount_in) = match function.name.as_str() {
+        name if name.starts_with("swapTokensSingleV3") => (
+            address_bytes(tokens.get(3)?),
+            tuple_field(tokens.get(5)?, 4)?.clone().into_uint()?,
+        ),
+        // The first step of a LI.FI swap, fee collection included, takes the whole input
+        name if name.starts_with("swapTokens") => (
+            address_bytes(tokens.get(3)?),
+            tuple_field(array_item(tokens.get(5)?, 0)?, 4)?.clone().into_uint()?,
+        ),
+        "createOrder" | "createSaltedOrder" => {
+            let order = tokens.first()?;
+            // Receivers on the destination chain are raw bytes, 20 of them for EVM chains
+            (tuple_field(order, 5)?.clone().into_bytes(), tuple_field(order, 1)?.clone().into_uint()?)
+        }
+        "swap" => {
+            let single_swap = tokens.first()?;
+            let recipient = address_bytes(tuple_field(tokens.get(1)?, 2)?);
+            // GIVEN_OUT swaps are bounded by the limit instead of the amount
+            let amount_in = if tuple_field(single_swap, 1)?.clone().into_uint()?.is_zero() {
+                tuple_field(single_swap, 4)?.clone().into_uint()?
+            } else {
+                tokens.get(2)?.clone().into_uint()?
+            };
+            (recipient, amount_in)
+        }
+        "batchSwap" => {
+            let swaps = tokens.get(1)?.clone().into_array()?;
+            let recipient = address_bytes(tuple_field(tokens.get(3)?, 2)?);
+            let asset_in_index = tuple_field(swaps.first()?, 1)?.clone().into_uint()?;
+            let amount_in = if tokens.first()?.clone().into_uint()?.is_zero() {
+                // Later hops of a multihop path carry a zero amount, so only the first legs add up
+                swaps.iter()
+                    .filter(|swap| tuple_field(swap, 1).and_then(|t| t.clone().into_uint()) == Some(asset_in_index))
+                    .filter_map(|swap| tuple_field(swap, 3).and_then(|t| t.clone().into_uint()))
+                    .fold(U256::zero(), |total, amount| total.saturating_add(amount))
+            } else {
+                let index = usize::try_from(asset_in_index.low_u64()).ok().filter(|_| asset_in_index.bits() <= 64)?;
+                array_item(tokens.get(4)?, index)?.clone().into_int()?
+            };
+            (recipient, amount_in)
+        }
+        _ => return None,
+    };
+
+    Some(DecodedCall {
+        function: function.name.clone(),
+        receiver,
+        amount_in,
+    })
+}
+
+// LI.FI has a facet per bridge, but all of them start with the same BridgeData
+fn decode_lifi_bridge_call(args: &[u8]) -> Option<DecodedCall> {
+    let bridge_data = decode(std::slice::from_ref(&*LIFI_BRIDGE_DATA), args).ok()?.into_iter().next()?;
+    let has_source_swaps = tuple_field(&bridge_data, 8)?.clone().into_bool()?;
+    let has_destination_call = tuple_field(&bridge_data, 9)?.clone().into_bool()?;
+
+    let amount_in = if has_source_swaps {
+        let tokens = decode(&[LIFI_BRIDGE_DATA.clone(), LIFI_SWAP_DATA.clone()], args).ok()?;
+        tuple_field(array_item(tokens.get(1)?, 0)?, 4)?.clone().into_uint()?
+    } else {
+        tuple_field(&bridge_data, 6)?.clone().into_uint()?
+    };
+
+    let receiver = tuple_field(&bridge_data, 5)?.clone().into_address()
+        .filter(|receiver| !has_destination_call && Address::from_str(LIFI_NON_EVM_RECEIVER).ok() != Some(*receiver))
+        .map(|receiver| receiver.as_bytes().to_vec());
+
+    Some(DecodedCall {
+        function: "bridgeFacet".to_string(),
+        receiver,
+        amount_in,
+    })
+}
+
+fn decode_call(tool: &str, data: &[u8]) -> Option<DecodedCall> {
+    let (selector, args) = (data.get(..4)?, data.get(4..)?);
+
+    let known = FUNCTIONS.iter()
+        .find(|(dapp, function)| *dapp == tool && function.short_signature() == selector)
+        .and_then(|(_, function)| decode_known_call(function, &function.decode_input(args).ok()?));
+
+    match known {
+        Some(call) => Some(call),
+        None if tool == "jumper" => decode_lifi_bridge_call(args),
+        None => None,
+    }
+}
+
+fn is_allowlisted(routers: &HashSet<Address>, address: &str) -> bool {
+    Address::from_str(address).is_ok_and(|address| routers.contains(&address))
+}
+
+// Checks the transaction a third-party API handed back before it reaches a user.
+// Errors reject the quote, API-driven dapps always get a `verification` entry.
+// Dapps whose calldata we encode ourselves are left alone
+pub fn verify_quote(quote: &mut Value) -> Result<(), String> {
+    let tool = quote["tool"].as_str().unwrap_or_default().to_string();
+    let Some(chains) = ALLOWLIST.get(&tool) else {
+        if UNLISTED_API_DAPPS.contains(&tool.as_str()) {
+            quote["verification"] = json!({
+                "verified": false,
+                "reason": format!("No allowlisted routers for {}, the transaction was not checked", tool)
+            });
+        }
+        return Ok(());
+    };
+
+    let chain_id = quote["fromChainId"].as_u64().ok_or("Invalid fromChainId")?;
+    let routers = chains.get(&chain_id)
+        .ok_or_else(|| format!("No allowlisted {} router on chain {}", tool, chain_id))?;
+
+    let to = quote["transaction"]["to"].as_str().unwrap_or("none");
+    if !is_allowlisted(routers, to) {
+        error!("Rejected {} quote on chain {}: transaction.to {} is not allowlisted", tool, chain_id, to);
+        return Err(format!("Transaction target {} is not an allowlisted {} router", to, tool));
+    }
+
+    // No approval means a native input, anything else must be a known spender
+    let approval_address = quote["approvalAddress"].as_str().unwrap_or("none");
+    let needs_approval = approval_address != "none"
+        && !Address::from_str(approval_address).is_ok_and(|address| address.is_zero());
+    if needs_approval && !is_allowlisted(routers, approval_address) {
+        error!("Rejected {} quote on chain {}: approvalAddress {} is not allowlisted", tool, chain_id, approval_address);
+        return Err(format!("Approval address {} is not an allowlisted {} spender", approval_address, tool));
+    }
+
+    let data = ethers::types::Bytes::from_str(quote["transaction"]["data"].as_str().unwrap_or("0x")).unwrap_or_default();
+    let selector = data.get(..4).map(|s| format!("0x{}", hex::encode(s))).unwrap_or_else(|| "none".to_string());
+
+    let Some(call) = decode_call(&tool, &data) else {
+        // The router is trusted, but the call itself couldn't be checked
+        debug!("{} selector {} is not decoded, flagging quote as unverified", tool, selector);
+        quote["verification"] = json!({
+            "verified": false,
+            "selector": selector,
+            "reason": "Calldata not decoded, only the router was checked"
+        });
+        return Ok(());
+    };
+
+    // Destination calls and non-EVM receivers can't be compared with toAddress, those quotes are only flagged
+    let to_address = quote["toAddress"].as_str().and_then(|a| Address::from_str(a).ok());
+    let receiver_checked = match (&call.receiver, to_address) {
+        (Some(receiver), Some(to_address)) => {
+            if receiver.as_slice() != to_address.as_bytes() {
+                error!("Rejected {} quote: {} pays 0x{} instead of toAddress", tool, call.function, hex::encode(receiver));
+                return Err(format!("Calldata receiver 0x{} does not match toAddress", hex::encode(receiver)));
+            }
+            true
+        }
+        _ => false,
+    };
+
+    // Exact-output quotes may pull up to fromAmountMax, exact-input ones exactly fromAmount
+    let (limit_field, exact) = match quote.get("fromAmountMax") {
+        Some(_) => ("fromAmountMax", false),
+        None => ("fromAmount", true),
+    };
+    let limit = quote[limit_field].as_str()
+        .and_then(|a| U256::from_dec_str(a).ok())
+        .ok_or_else(|| format!("Invalid {}", limit_field))?;
+    if (exact && call.amount_in != limit) || call.amount_in > limit {
+        error!("Rejected {} quote: {} pulls {} against {} {}", tool, call.function, call.amount_in, limit_field, limit);
+        return Err(format!("Calldata input amount {} does not match {} {}", call.amount_in, limit_field, limit));
+    }
+
+    quote["verification"] = if receiver_checked {
+        json!({
+            "verified": true,
+            "selector": selector,
+            "function": call.function
+        })
+    } else {
+        json!({
+            "verified": false,
+            "selector": selector,
+            "function": call.function,
+            "reason": "Receiver could not be compared with toAddress, only the router and input amount were checked"
+        })
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::encode;
+
+    const RECEIVER: &str = "0x2222222222222222222222222222222222222222";
+    const TOKEN: &str = "0x3333333333333333333333333333333333333333";
+
+    fn address(value: &str) -> Token {
+        Token::Address(Address::from_str(value).unwrap())
+    }
+
+    fn uint(value: u64) -> Token {
+        Token::Uint(U256::from(value))
+    }
+
+    fn lifi_bridge_data(receiver: &str, min_amount: u64, has_source_swaps: bool, has_destination_call: bool) -> Token {
+        Token::Tuple(vec![
+            Token::FixedBytes(vec![0x11; 32]),
+            Token::String("stargate".to_string()),
+            Token::String("aggregate".to_string()),
+            address(TOKEN),
+            address(TOKEN),
+            address(receiver),
+            uint(min_amount),
+            uint(10),
+            Token::Bool(has_source_swaps),
+            Token::Bool(has_destination_call),
+        ])
+    }
+
+    fn lifi_swap_data(from_amount: u64) -> Token {
+        Token::Array(vec![Token::Tuple(vec![
+            address(TOKEN),
+            address(TOKEN),
+            address(TOKEN),
+            address(TOKEN),
+            uint(from_amount),
+            Token::Bytes(vec![0xab; 8]),
+            Token::Bool(true),
+        ])])
+    }
+
+    fn balancer_swap(kind: u64, amount: u64, limit: u64) -> Vec<u8> {
+        let function = &FUNCTIONS.iter().find(|(dapp, function)| *dapp == "balancer" && function.name == "swap").unwrap().1;
+        let single_swap = Token::Tuple(vec![
+            Token::FixedBytes(vec![0x44; 32]),
+            Token::Uint(U256::from(kind)),
+            address(TOKEN),
+            address(RECEIVER),
+            uint(amount),
+            Token::Bytes(vec![]),
+        ]);
+        let funds = Token::Tuple(vec![address(RECEIVER), Token::Bool(false), address(RECEIVER), Token::Bool(false)]);
+        function.encode_input(&[single_swap, funds, uint(limit), uint(1_900_000_000)]).unwrap()
+    }
+
+    #[test]
+    fn lifi_bridge_call_reads_receiver_and_min_amount() {
+        // Facets append their own bridge-specific data after BridgeData
+        let args = encode(&[lifi_bridge_data(RECEIVER, 5_000, false, false), Token::Tuple(vec![uint(1), address(TOKEN)])]);
+        let call = decode_lifi_bridge_call(&args).unwrap();
+        assert_eq!(call.receiver, Some(Address::from_str(RECEIVER).unwrap().as_bytes().to_vec()));
+        assert_eq!(call.amount_in, U256::from(5_000u64));
+    }
+
+    #[test]
+    fn lifi_bridge_call_with_source_swaps_takes_the_first_swap_amount() {
+        let args = encode(&[lifi_bridge_data(RECEIVER, 4_900, true, false), lifi_swap_data(5_000)]);
+        let call = decode_lifi_bridge_call(&args).unwrap();
+        assert_eq!(call.amount_in, U256::from(5_000u64));
+    }
+
+    #[test]
+    fn lifi_bridge_call_leaves_unverifiable_receivers_empty() {
+        let args = encode(&[lifi_bridge_data(RECEIVER, 5_000, false, true)]);
+        assert_eq!(decode_lifi_bridge_call(&args).unwrap().receiver, None);
+
+        let args = encode(&[lifi_bridge_data(LIFI_NON_EVM_RECEIVER, 5_000, false, false)]);
+        assert_eq!(decode_lifi_bridge_call(&args).unwrap().receiver, None);
+    }
+
+    #[test]
+    fn lifi_bridge_call_rejects_garbage() {
+        assert!(decode_lifi_bridge_call(&[0u8; 16]).is_none());
+    }
+
+    #[test]
+    fn balancer_given_in_swap_pulls_the_swap_amount() {
+        let call = decode_call("balancer", &balancer_swap(0, 1_000, 990)).unwrap();
+        assert_eq!(call.function, "swap");
+        assert_eq!(call.receiver, Some(Address::from_str(RECEIVER).unwrap().as_bytes().to_vec()));
+        assert_eq!(call.amount_in, U256::from(1_000u64));
+    }
+
+    #[test]
+    fn balancer_given_out_swap_is_bounded_by_the_limit() {
+        let call = decode_call("balancer", &balancer_swap(1, 1_000, 1_010)).unwrap();
+        assert_eq!(call.amount_in, U256::from(1_010u64));
+    }
+
+    #[test]
+    fn unknown_selectors_are_not_decoded() {
+        assert!(decode_call("balancer", &[0xde, 0xad, 0xbe, 0xef, 0x00]).is_none());
+        assert!(decode_call("bungee", &balancer_swap(0, 1_000, 990)).is_none());
+    }
+
+    #[test]
+    fn unlisted_api_dapps_are_flagged_unverified() {
+        for tool in UNLISTED_API_DAPPS {
+            let mut quote = json!({ "tool": tool, "fromChainId": 1, "transaction": { "to": RECEIVER, "data": "0x" } });
+            verify_quote(&mut quote).unwrap();
+            assert_eq!(quote["verification"]["verified"], json!(false), "{} should be flagged", tool);
+        }
+
+        // Locally encoded calldata needs no third-party check
+        let mut quote = json!({ "tool": "hyphen", "fromChainId": 137, "transaction": { "to": RECEIVER, "data": "0x" } });
+        verify_quote(&mut quote).unwrap();
+        assert!(quote.get("verification").is_none());
+    }
+
+    #[test]
+    fn verify_quote_checks_lifi_bridge_receiver_and_amount() {
+        let router = "0x1231DEB6f5749EF6cE6943a275A1D3E7486F4EaE";
+        let mut data = vec![0x12, 0x34, 0x56, 0x78];
+        data.extend(encode(&[lifi_bridge_data(RECEIVER, 5_000, false, false)]));
+        let mut quote = json!({
+            "tool": "jumper",
+            "fromChainId": 1,
+            "fromAmount": "5000",
+            "toAddress": RECEIVER,
+            "approvalAddress": router,
+            "transaction": { "to": router, "data": format!("0x{}", hex::encode(&data)) }
+        });
+
+        verify_quote(&mut quote).unwrap();
+        assert_eq!(quote["verification"]["verified"], json!(true));
+
+        quote["fromAmount"] = json!("4999");
+        assert!(verify_quote(&mut quote).is_err());
+
+        quote["fromAmount"] = json!("5000");
+        quote["toAddress"] = json!("0x4444444444444444444444444444444444444444");
+        assert!(verify_quote(&mut quote).is_err());
+    }
+}
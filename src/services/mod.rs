@@ -1,6 +1,10 @@
 pub mod quote_service;
 pub mod quote_router;
+pub mod quote_ranking;
 pub mod quote_stream_router;
 pub mod quote_direct_router;
 pub mod transaction_router;
-//pub mod approval;
\ No newline at end of file
+pub mod approval;
+pub mod permit;
+pub mod calldata_verifier;
+pub mod simulation;
\ No newline at end of file
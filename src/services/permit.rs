@@ -0,0 +1,237 @@
+//src/services/permit.rs
+use ethers::abi::{encode, Token};
+use ethers::prelude::*;
+use ethers::types::{Address, H256, Signature, U256};
+use ethers::utils::keccak256;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::load_resources::AppState;
+use crate::dapps::adapter::{PermitKind, PermitSignature};
+use crate::services::approval::required_allowance;
+use crate::utils::allowance_checker::get_user_allowance;
+use crate::utils::utils::get_random_rpc_proxy_provider;
+use tracing::debug;
+
+// How long a permit signature stays usable
+const PERMIT_VALIDITY_SECS: u64 = 1800;
+
+const PERMIT_ABI: &str = r#"[
+    {"inputs":[{"name":"owner","type":"address"}],"name":"nonces","outputs":[{"name":"","type":"uint256"}],"stateMutability":"view","type":"function"},
+    {"inputs":[],"name":"DOMAIN_SEPARATOR","outputs":[{"name":"","type":"bytes32"}],"stateMutability":"view","type":"function"},
+    {"inputs":[],"name":"name","outputs":[{"name":"","type":"string"}],"stateMutability":"view","type":"function"},
+    {"inputs":[],"name":"version","outputs":[{"name":"","type":"string"}],"stateMutability":"view","type":"function"}
+]"#;
+
+lazy_static::lazy_static! {
+    static ref PERMIT_CONTRACT_ABI: ethers::abi::Abi = serde_json::from_str(PERMIT_ABI).expect("Failed to parse permit ABI");
+}
+
+// The token, parties and amount a permit covers, taken from the cached quote
+struct PermitContext {
+    chain_id: u64,
+    token: Address,
+    owner: Address,
+    spender: Address,
+    value: U256,
+}
+
+impl PermitContext {
+    fn from_quote(quote: &Value) -> Result<Self, String> {
+        let parse = |value: &Value, field: &str| value.as_str()
+            .ok_or_else(|| format!("Missing {}", field))
+            .and_then(|address| Address::from_str(address).map_err(|e| format!("Invalid {}: {}", field, e)));
+
+        Ok(PermitContext {
+            chain_id: quote["fromChainId"].as_u64().ok_or("Invalid fromChainId")?,
+            token: parse(&quote["fromToken"]["address"], "fromToken address")?,
+            owner: parse(&quote["fromAddress"], "fromAddress")?,
+            spender: parse(&quote["approvalAddress"], "approvalAddress")?,
+            value: required_allowance(quote)?,
+        })
+    }
+}
+
+// Everything needed to build the EIP-712 payload for one permit kind
+struct PermitDomain {
+    kind: PermitKind,
+    // The EIP712Domain object handed to the wallet
+    domain: Value,
+    domain_separator: [u8; 32],
+    nonce: U256,
+}
+
+fn eip2612_domain_separator(name: &str, version: &str, chain_id: u64, token: Address) -> [u8; 32] {
+    keccak256(encode(&[
+        Token::FixedBytes(keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)").to_vec()),
+        Token::FixedBytes(keccak256(name).to_vec()),
+        Token::FixedBytes(keccak256(version).to_vec()),
+        Token::Uint(U256::from(chain_id)),
+        Token::Address(token),
+    ]))
+}
+
+// A token supports EIP-2612 when it exposes nonces and a DOMAIN_SEPARATOR we can reproduce
+async fn resolve_eip2612(provider: Arc<Provider<Http>>, context: &PermitContext) -> Option<PermitDomain> {
+    let token = Contract::new(context.token, PERMIT_CONTRACT_ABI.clone(), provider);
+
+    let domain_separator: [u8; 32] = token.method::<_, [u8; 32]>("DOMAIN_SEPARATOR", ()).ok()?.call().await.ok()?;
+    let nonce: U256 = token.method::<_, U256>("nonces", context.owner).ok()?.call().await.ok()?;
+    let name: String = token.method::<_, String>("name", ()).ok()?.call().await.ok()?;
+
+    // Most tokens don't expose version(), so the common ones are tried against the separator
+    let mut versions = vec!["1".to_string(), "2".to_string()];
+    if let Ok(version) = token.method::<_, String>("version", ()).ok()?.call().await {
+        versions.insert(0, version);
+    }
+
+    let version = versions.into_iter()
+        .find(|version| eip2612_domain_separator(&name, version, context.chain_id, context.token) == domain_separator)?;
+
+    Some(PermitDomain {
+        kind: PermitKind::Eip2612,
+        domain: json!({
+            "name": name,
+            "version": version,
+            "chainId": context.chain_id,
+            "verifyingContract": format!("{:?}", context.token)
+        }),
+        domain_separator,
+        nonce,
+    })
+}
+
+async fn resolve_permit_domain(kind: PermitKind, context: &PermitContext, state: &Arc<AppState>) -> Result<Option<PermitDomain>, String> {
+    let provider = get_random_rpc_proxy_provider(context.chain_id, &state.rpc_proxy_providers)
+        .ok_or_else(|| format!("No RPC provider found for chain ID: {}", context.chain_id))?;
+
+    Ok(match kind {
+        PermitKind::Eip2612 => resolve_eip2612(provider, context).await,
+    })
+}
+
+// The EIP-712 struct hash of the permit message, mirroring typed_data below
+fn struct_hash(domain: &PermitDomain, context: &PermitContext, deadline: U256) -> [u8; 32] {
+    match domain.kind {
+        PermitKind::Eip2612 => keccak256(encode(&[
+            Token::FixedBytes(keccak256("Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)").to_vec()),
+            Token::Address(context.owner),
+            Token::Address(context.spender),
+            Token::Uint(context.value),
+            Token::Uint(domain.nonce),
+            Token::Uint(deadline),
+        ])),
+    }
+}
+
+// eth_signTypedData_v4 payload for the wallet
+fn typed_data(domain: &PermitDomain, context: &PermitContext, deadline: U256) -> Value {
+    match domain.kind {
+        PermitKind::Eip2612 => json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Permit": [
+                    { "name": "owner", "type": "address" },
+                    { "name": "spender", "type": "address" },
+                    { "name": "value", "type": "uint256" },
+                    { "name": "nonce", "type": "uint256" },
+                    { "name": "deadline", "type": "uint256" }
+                ]
+            },
+            "primaryType": "Permit",
+            "domain": domain.domain,
+            "message": {
+                "owner": format!("{:?}", context.owner),
+                "spender": format!("{:?}", context.spender),
+                "value": context.value.to_string(),
+                "nonce": domain.nonce.to_string(),
+                "deadline": deadline.to_string()
+            }
+        }),
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+// Typed data for the first permit kind the token supports, or None when no permit is needed or possible
+pub async fn prepare_permit(quote: &Value, kinds: &[PermitKind], state: &Arc<AppState>) -> Result<Option<Value>, String> {
+    let context = PermitContext::from_quote(quote)?;
+
+    // Native tokens and sufficient allowances need neither a permit nor an approve
+    match get_user_allowance(quote, state).await? {
+        Some(allowance) if allowance < context.value => {}
+        _ => return Ok(None),
+    }
+
+    for kind in kinds {
+        if let Some(domain) = resolve_permit_domain(*kind, &context, state).await? {
+            let deadline = U256::from(now_secs() + PERMIT_VALIDITY_SECS);
+            return Ok(Some(json!({
+                "permitRequired": true,
+                "permit": {
+                    "kind": domain.kind,
+                    "value": context.value.to_string(),
+                    "nonce": domain.nonce.to_string(),
+                    "deadline": deadline.to_string(),
+                    "typedData": typed_data(&domain, &context, deadline)
+                }
+            })));
+        }
+        debug!("Token {:?} does not support {:?} permits", context.token, kind);
+    }
+
+    Ok(None)
+}
+
+// Checks a signature sent back to build-transaction against the owner before any calldata uses it
+pub async fn verify_permit(quote: &Value, permit: &Value, kinds: &[PermitKind], state: &Arc<AppState>) -> Result<PermitSignature, String> {
+    let context = PermitContext::from_quote(quote)?;
+
+    let kind: PermitKind = serde_json::from_value(permit["kind"].clone())
+        .map_err(|e| format!("Invalid permit kind: {}", e))?;
+    if !kinds.contains(&kind) {
+        return Err(format!("Permit kind {:?} is not supported by {}", kind, quote["tool"].as_str().unwrap_or("this dapp")));
+    }
+
+    let deadline = permit["deadline"].as_str()
+        .and_then(|deadline| U256::from_dec_str(deadline).ok())
+        .or_else(|| permit["deadline"].as_u64().map(U256::from))
+        .ok_or("Invalid permit deadline")?;
+    if deadline <= U256::from(now_secs()) {
+        return Err("Permit deadline has passed, request a new permit".to_string());
+    }
+
+    let signature = Signature::from_str(permit["signature"].as_str().ok_or("Missing permit signature")?)
+        .map_err(|e| format!("Invalid permit signature: {}", e))?;
+
+    let domain = resolve_permit_domain(kind, &context, state).await?
+        .ok_or("Token no longer supports this permit")?;
+
+    let digest = keccak256([
+        &[0x19, 0x01][..],
+        &domain.domain_separator[..],
+        &struct_hash(&domain, &context, deadline)[..],
+    ].concat());
+
+    let signer = signature.recover(H256::from(digest))
+        .map_err(|e| format!("Failed to recover permit signer: {}", e))?;
+    if signer != context.owner {
+        return Err("Permit was not signed by fromAddress".to_string());
+    }
+
+    Ok(PermitSignature {
+        kind,
+        value: context.value,
+        nonce: domain.nonce,
+        deadline,
+        signature,
+    })
+}
@@ -0,0 +1,244 @@
+//src/services/quote_ranking.rs
+use ethers::types::U256;
+use serde_json::{Value, json};
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    // Highest toAmount in token units, or lowest fromAmount for exact-output quotes
+    Output,
+    // Highest toAmountUSD after gas and additional fees
+    Net,
+    // Cheapest gas cost in USD
+    Gas,
+    // Fastest estimated delivery
+    Speed,
+}
+
+impl SortBy {
+    // Unknown or missing values fall back to net value ranking
+    pub fn from_options(options: &Value) -> Self {
+        match options["sortBy"].as_str().map(|s| s.to_lowercase()).as_deref() {
+            Some("output") => SortBy::Output,
+            Some("gas") => SortBy::Gas,
+            Some("speed") => SortBy::Speed,
+            _ => SortBy::Net,
+        }
+    }
+}
+
+// Quote fields are formatted strings where "none" means the value is unknown
+fn usd_field(value: &Value) -> Option<f64> {
+    value.as_str().and_then(|s| s.parse::<f64>().ok()).or_else(|| value.as_f64())
+}
+
+fn to_amount(quote: &Value) -> U256 {
+    U256::from_dec_str(quote["toAmount"].as_str().unwrap_or("0")).unwrap_or_default()
+}
+
+fn from_amount(quote: &Value) -> U256 {
+    U256::from_dec_str(quote["fromAmount"].as_str().unwrap_or("0")).unwrap_or_default()
+}
+
+// Exact-output quotes fix toAmount, so they compete on what they take in
+fn is_exact_out(quote: &Value) -> bool {
+    quote.get("fromAmountMax").is_some()
+}
+
+// Gas plus any native fee paid on top, in USD; None when the gas cost is unknown
+fn cost_usd(quote: &Value) -> Option<f64> {
+    let swap_cost_usd = usd_field(&quote["swapCostUSD"])?;
+    let fee_usd = usd_field(&quote["additionalFee"]["amountUSD"]).unwrap_or(0.0);
+    Some(swap_cost_usd + fee_usd)
+}
+
+// What the user ends up with in USD: output minus gas and any native fee paid on top
+pub fn net_value_usd(quote: &Value) -> Option<f64> {
+    let to_amount_usd = usd_field(&quote["toAmountUSD"])?;
+    Some(to_amount_usd - cost_usd(quote)?)
+}
+
+// What an exact-output quote costs in USD: input plus gas and fees
+fn total_spend_usd(quote: &Value) -> Option<f64> {
+    let from_amount_usd = usd_field(&quote["fromAmountUSD"])?;
+    Some(from_amount_usd + cost_usd(quote)?)
+}
+
+// Known values rank ahead of unknown ones regardless of direction, so a quote without a
+// gas cost never looks free next to one that reports it
+fn compare_known<T: PartialOrd>(a: Option<T>, b: Option<T>, descending: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let ordering = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+            if descending { ordering.reverse() } else { ordering }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn compare_output(a: &Value, b: &Value) -> Ordering {
+    if is_exact_out(a) && is_exact_out(b) {
+        from_amount(a).cmp(&from_amount(b))
+    } else {
+        to_amount(b).cmp(&to_amount(a))
+    }
+}
+
+fn compare_net(a: &Value, b: &Value) -> Ordering {
+    let ordering = if is_exact_out(a) && is_exact_out(b) {
+        compare_known(total_spend_usd(a), total_spend_usd(b), false)
+    } else {
+        compare_known(net_value_usd(a), net_value_usd(b), true)
+    };
+    ordering.then_with(|| compare_output(a, b))
+}
+
+fn compare_quotes(a: &Value, b: &Value, sort_by: SortBy) -> Ordering {
+    match sort_by {
+        SortBy::Output => compare_output(a, b).then_with(|| compare_net(a, b)),
+        SortBy::Net => compare_net(a, b),
+        SortBy::Gas => compare_known(usd_field(&a["swapCostUSD"]), usd_field(&b["swapCostUSD"]), false)
+            .then_with(|| compare_net(a, b)),
+        SortBy::Speed => compare_known(a["estimatedTime"].as_u64(), b["estimatedTime"].as_u64(), false)
+            .then_with(|| compare_net(a, b)),
+    }
+}
+
+// Sorts route entries ({id, name, data}) best first and tags the winner as recommended
+pub fn rank_quotes(results: &mut [Value], sort_by: SortBy) {
+    results.sort_by(|a, b| compare_quotes(&a["data"], &b["data"], sort_by));
+
+    for (index, result) in results.iter_mut().enumerate() {
+        let net_value = net_value_usd(&result["data"])
+            .map(|value| format!("{:.2}", value))
+            .unwrap_or_else(|| "none".to_string());
+
+        if let Some(data) = result["data"].as_object_mut() {
+            data.insert("netValueUSD".to_string(), json!(net_value));
+        }
+        if let Some(entry) = result.as_object_mut() {
+            entry.insert("recommended".to_string(), json!(index == 0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, data: Value) -> Value {
+        json!({ "name": name, "data": data })
+    }
+
+    fn exact_in(to_amount: &str, to_amount_usd: &str, swap_cost_usd: &str) -> Value {
+        json!({
+            "toAmount": to_amount,
+            "toAmountUSD": to_amount_usd,
+            "swapCostUSD": swap_cost_usd
+        })
+    }
+
+    fn names(results: &[Value]) -> Vec<&str> {
+        results.iter().map(|result| result["name"].as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn net_value_subtracts_gas_and_native_fee() {
+        let mut quote = exact_in("1000", "100.00", "1.500");
+        quote["additionalFee"] = json!({ "amountUSD": "0.500" });
+        assert_eq!(net_value_usd(&quote), Some(98.0));
+    }
+
+    #[test]
+    fn net_value_is_unknown_without_gas_cost() {
+        assert_eq!(net_value_usd(&exact_in("1000", "100.00", "none")), None);
+        assert_eq!(net_value_usd(&exact_in("1000", "none", "1.000")), None);
+    }
+
+    #[test]
+    fn net_ranks_unknown_gas_after_known_gas() {
+        let mut results = vec![
+            entry("bridge", exact_in("1010", "101.00", "none")),
+            entry("aggregator", exact_in("1000", "100.00", "2.000")),
+        ];
+        rank_quotes(&mut results, SortBy::Net);
+
+        assert_eq!(names(&results), vec!["aggregator", "bridge"]);
+        assert_eq!(results[0]["recommended"], json!(true));
+        assert_eq!(results[0]["data"]["netValueUSD"], json!("98.00"));
+        assert_eq!(results[1]["recommended"], json!(false));
+        assert_eq!(results[1]["data"]["netValueUSD"], json!("none"));
+    }
+
+    #[test]
+    fn net_prefers_higher_value_after_costs() {
+        let mut results = vec![
+            entry("cheap_gas", exact_in("990", "99.00", "0.100")),
+            entry("more_output", exact_in("1000", "100.00", "3.000")),
+        ];
+        rank_quotes(&mut results, SortBy::Net);
+        assert_eq!(names(&results), vec!["cheap_gas", "more_output"]);
+    }
+
+    #[test]
+    fn output_ignores_costs() {
+        let mut results = vec![
+            entry("cheap_gas", exact_in("990", "99.00", "0.100")),
+            entry("more_output", exact_in("1000", "100.00", "none")),
+        ];
+        rank_quotes(&mut results, SortBy::Output);
+        assert_eq!(names(&results), vec!["more_output", "cheap_gas"]);
+    }
+
+    #[test]
+    fn gas_ranks_cheapest_known_cost_first() {
+        let mut results = vec![
+            entry("unknown", exact_in("1000", "100.00", "none")),
+            entry("expensive", exact_in("1000", "100.00", "2.000")),
+            entry("cheap", exact_in("1000", "100.00", "0.200")),
+        ];
+        rank_quotes(&mut results, SortBy::Gas);
+        assert_eq!(names(&results), vec!["cheap", "expensive", "unknown"]);
+    }
+
+    #[test]
+    fn speed_ranks_fastest_first() {
+        let mut slow = exact_in("1000", "100.00", "1.000");
+        slow["estimatedTime"] = json!(600);
+        let mut fast = exact_in("1000", "100.00", "1.000");
+        fast["estimatedTime"] = json!(30);
+        let mut results = vec![entry("slow", slow), entry("fast", fast)];
+        rank_quotes(&mut results, SortBy::Speed);
+        assert_eq!(names(&results), vec!["fast", "slow"]);
+    }
+
+    #[test]
+    fn exact_out_quotes_compete_on_total_spend() {
+        let exact_out = |from_amount: &str, from_amount_usd: &str, swap_cost_usd: &str| json!({
+            "toAmount": "1000",
+            "fromAmount": from_amount,
+            "fromAmountMax": from_amount,
+            "fromAmountUSD": from_amount_usd,
+            "toAmountUSD": "100.00",
+            "swapCostUSD": swap_cost_usd
+        });
+        let mut results = vec![
+            entry("less_input", exact_out("1010", "101.00", "3.000")),
+            entry("cheaper_total", exact_out("1015", "101.50", "0.500")),
+        ];
+        rank_quotes(&mut results, SortBy::Net);
+        assert_eq!(names(&results), vec!["cheaper_total", "less_input"]);
+
+        rank_quotes(&mut results, SortBy::Output);
+        assert_eq!(names(&results), vec!["less_input", "cheaper_total"]);
+    }
+
+    #[test]
+    fn sort_by_defaults_to_net() {
+        assert_eq!(SortBy::from_options(&json!({ "sortBy": "GAS" })), SortBy::Gas);
+        assert_eq!(SortBy::from_options(&json!({ "sortBy": "fastest" })), SortBy::Net);
+        assert_eq!(SortBy::from_options(&json!({})), SortBy::Net);
+    }
+}
@@ -1,17 +1,87 @@
 //src/services/quote_router.rs
 use crate::utils::filter_dapps::filter_dapps;
-use crate::dapps::AVAILABLE_SERVICES;
-use crate::utils::utils::fetch_gas_price;
+use crate::dapps::ADAPTER_REGISTRY;
+use crate::dapps::adapter::{DappAdapter, QuoteErrorKind, QuoteRequest};
+use crate::utils::utils::BITCOIN_CHAIN_ID;
+use crate::utils::gas_cache::get_fee_estimate;
+use crate::dapps::symbiosis::bitcoin_token_details;
+use crate::services::quote_ranking::{rank_quotes, SortBy};
+use crate::services::calldata_verifier::verify_quote;
+use crate::services::simulation::attach_simulation;
 use crate::load_resources::AppState;
 use crate::utils::fetch_token_details::fetch_token_details;
 use serde_json::{Value, json};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::timeout;
 use std::sync::Arc;
 use tracing::{debug, error};
 use futures::future::join_all;
 
-pub async fn route_quote(params: Value, state: Arc<AppState>) -> Result<Value, String> {
+pub type ServiceList = Vec<(String, &'static dyn DappAdapter)>;
+
+// What route_quote and route_quote_stream need before any adapter runs
+pub enum QuotePlan {
+    // `skipped` holds an `unsupported` error entry for every dapp the router won't try
+    Ready { request: Box<QuoteRequest>, services: ServiceList, skipped: Vec<Value> },
+    // Nothing can quote this request; carries the response to return as is
+    Unavailable(Value),
+}
+
+// Error entry for a dapp that was never run, shaped like the ones quote_with_adapter returns
+fn skipped_entry(name: &str, message: String) -> Value {
+    json!({
+        "name": name,
+        "category": QuoteErrorKind::Unsupported,
+        "message": message,
+        "elapsedMs": 0
+    })
+}
+
+// Picks the adapters to run. Only dapps the caller named explicitly are reported when skipped,
+// the default set is filtered silently
+fn select_services(
+    requested_dapps: Vec<String>,
+    available_dapps_names: &[String],
+    from_token_address: &str,
+    (from_chain_id, to_chain_id): (u64, u64),
+    exact_out: bool,
+) -> (ServiceList, Vec<Value>) {
+    let explicit = !requested_dapps.is_empty();
+    let candidates = if explicit { requested_dapps } else { available_dapps_names.to_vec() };
+
+    // Adapters that can't serve this route (same-chain vs cross-chain, bundled chains, exact-out) are skipped up front
+    let mut services_to_run: ServiceList = Vec::new();
+    let mut skipped = Vec::new();
+    for dapp in candidates {
+        let Some(adapter) = ADAPTER_REGISTRY.get(&dapp) else {
+            if explicit {
+                skipped.push(skipped_entry(&dapp, "Unknown dapp".to_string()));
+            }
+            continue;
+        };
+        let capabilities = adapter.capabilities();
+        let skip_reason = if !available_dapps_names.contains(&dapp) {
+            Some(format!("Not enabled for {} on chain {} to {}", from_token_address, from_chain_id, to_chain_id))
+        } else if !capabilities.supports_route(from_chain_id, to_chain_id) {
+            Some(format!("Does not support chain {} to {}", from_chain_id, to_chain_id))
+        } else if !capabilities.supports_mode(exact_out) {
+            let mode = if exact_out { "exact-output" } else { "exact-input" };
+            Some(format!("Does not support {} quotes", mode))
+        } else {
+            None
+        };
+
+        match skip_reason {
+            Some(reason) if explicit => skipped.push(skipped_entry(&dapp, reason)),
+            Some(reason) => debug!("Skipping {}: {}", dapp, reason),
+            None => services_to_run.push((dapp, adapter)),
+        }
+    }
+
+    (services_to_run, skipped)
+}
+
+pub async fn prepare_quote(params: &Value, state: &Arc<AppState>) -> Result<QuotePlan, String> {
     // Clone the params and state to avoid lifetime issues in tasks
     let mut extended_params = params.clone();
     let from_chain_id = params["fromChainId"].as_u64().ok_or("Invalid fromChainId")?;
@@ -22,11 +92,13 @@ pub async fn route_quote(params: Value, state: Arc<AppState>) -> Result<Value, S
     // Define the native zero address
     let zero_address = "0x0000000000000000000000000000000000000000";
 
-    let gas_prices = fetch_gas_price(from_chain_id, Arc::clone(&state)).await
+    let (fee_estimate, gas_data_age) = get_fee_estimate(from_chain_id, state).await
         .map_err(|e| format!("Failed to fetch gas prices: {}", e))?;
 
     // Extend params with gas prices and set quoteOnly
-    extended_params["gasPrices"] = json!(gas_prices);
+    extended_params["gasPrices"] = json!(fee_estimate.gas_prices());
+    extended_params["feeData"] = fee_estimate.to_json();
+    extended_params["feeData"]["ageMs"] = json!(gas_data_age.as_millis() as u64);
     extended_params["quoteOnly"] = json!(true);
 
     // Filter available dapps
@@ -34,25 +106,43 @@ pub async fn route_quote(params: Value, state: Arc<AppState>) -> Result<Value, S
         from_token_address,
         from_chain_id,
         to_chain_id,
-        Arc::clone(&state)
+        Arc::clone(state)
     );
 
     debug!("Available DApps: {:?}", available_dapps_names);
 
+    // Exact-output quotes carry toAmount instead of amount
+    let exact_out = params["toAmount"].as_str().is_some_and(|a| !a.is_empty());
+
+    let requested_dapps: Vec<String> = params["options"]["dapps"].as_array()
+        .map(|dapps| dapps.iter().filter_map(|dapp| dapp.as_str().map(|d| d.to_string())).collect())
+        .unwrap_or_default();
+
+    let (services_to_run, skipped) = select_services(requested_dapps, &available_dapps_names, from_token_address, (from_chain_id, to_chain_id), exact_out);
+
     if available_dapps_names.is_empty() {
-        return Ok(json!({
+        return Ok(QuotePlan::Unavailable(json!({
+            "success": false,
+            "message": "No dApps available",
+            "errors": skipped
+        })));
+    }
+
+    if services_to_run.is_empty() {
+        return Ok(QuotePlan::Unavailable(json!({
             "success": false,
-            "message": "No dApps available"
-        }));
+            "message": "No valid quotes found.",
+            "errors": skipped
+        })));
     }
 
     // Create a longer-lived Arc clone
-    let state_clone = Arc::clone(&state);
+    let state_clone = Arc::clone(state);
 
     // Pass all tokens (fromToken, toToken, and the zero address token) to fetch_token_details at once
     let tokens_with_chain_ids = vec![
         (from_token_address, from_chain_id),
-        (to_token_address, to_chain_id),
+        if to_chain_id == BITCOIN_CHAIN_ID { (zero_address, from_chain_id) } else { (to_token_address, to_chain_id) },
         (zero_address, from_chain_id), // Native token (zero address)
     ];
 
@@ -61,94 +151,86 @@ pub async fn route_quote(params: Value, state: Arc<AppState>) -> Result<Value, S
 
     // Assume token_details contains details for all tokens in the order they were passed
     let from_token_details = &token_details[0];
-    let to_token_details = &token_details[1];
+    // Bitcoin has no entry in the token list, so its details are filled in directly
+    let to_token_details = if to_chain_id == BITCOIN_CHAIN_ID {
+        Some(bitcoin_token_details())
+    } else {
+        token_details[1].clone()
+    };
     let native_token_details = &token_details[2];
 
     extended_params["fromTokenDetails"] = json!(from_token_details);
     extended_params["toTokenDetails"] = json!(to_token_details);
     extended_params["nativeTokenDetails"] = json!(native_token_details); 
 
-    let services_to_run: Vec<_> = if let Some(options) = params.get("options") {
-        if let Some(dapps) = options.get("dapps").and_then(|d| d.as_array()) {
-            if dapps.is_empty() {
-                // If dapps array is empty, use all available dapps
-                available_dapps_names.iter()
-                    .filter(|dapp| AVAILABLE_SERVICES.contains_key(dapp.as_str()))
-                    .map(|dapp| (dapp.to_string(), AVAILABLE_SERVICES[dapp.as_str()]))
-                    .collect()
-            } else {
-                // If dapps array is not empty, use only the specified dapps
-                dapps.iter()
-                    .filter_map(|dapp| dapp.as_str())
-                    .filter(|&dapp| available_dapps_names.contains(&dapp.to_string()) && AVAILABLE_SERVICES.contains_key(dapp))
-                    .map(|dapp| (dapp.to_string(), AVAILABLE_SERVICES[dapp]))
-                    .collect()
-            }
-        } else {
-            // If dapps key is not present, use all available dapps
-            available_dapps_names.iter()
-                .filter(|dapp| AVAILABLE_SERVICES.contains_key(dapp.as_str()))
-                .map(|dapp| (dapp.to_string(), AVAILABLE_SERVICES[dapp.as_str()]))
-                .collect()
-        }
-    } else {
-        // If options is not present, use all available dapps
-        available_dapps_names.iter()
-            .filter(|dapp| AVAILABLE_SERVICES.contains_key(dapp.as_str()))
-            .map(|dapp| (dapp.to_string(), AVAILABLE_SERVICES[dapp.as_str()]))
-            .collect()
+    let request = QuoteRequest::from_params(extended_params)?;
+
+    Ok(QuotePlan::Ready { request: Box::new(request), services: services_to_run, skipped })
+}
+
+// Runs one adapter with a timeout, returning the named quote or a categorized error entry
+pub async fn quote_with_adapter(name: String, adapter: &'static dyn DappAdapter, request: QuoteRequest, state: Arc<AppState>) -> Result<Value, Value> {
+    let started = Instant::now();
+    let outcome = match timeout(Duration::from_secs(30), adapter.quote(request, Arc::clone(&state))).await {
+        Ok(Ok(response)) => serde_json::to_value(response)
+            .map_err(|e| (QuoteErrorKind::Other, format!("Failed to serialize quote: {}", e)))
+            .and_then(|mut value| verify_quote(&mut value)
+                .map(|_| value)
+                .map_err(|e| (QuoteErrorKind::UnsafeCalldata, e))),
+        Ok(Err(e)) => Err((e.kind, e.message)),
+        Err(_) => Err((QuoteErrorKind::Timeout, "Timed out after 30s".to_string())),
     };
+    let elapsed_ms = started.elapsed().as_millis() as u64;
 
-    if services_to_run.is_empty() {
-        return Ok(json!({
-            "success": false,
-            "message": "No valid quotes found."
-        }));
+    match outcome {
+        Ok(mut value) => {
+            attach_simulation(&mut value, Duration::from_secs(30).saturating_sub(started.elapsed()), &state).await;
+            Ok(json!({
+                "name": name,
+                "data": value
+            }))
+        }
+        Err((kind, message)) => {
+            error!("Error in {} ({:?}, {}ms): {}", name, kind, elapsed_ms, message);
+            Err(json!({
+                "name": name,
+                "category": kind,
+                "message": message,
+                "elapsedMs": elapsed_ms
+            }))
+        }
     }
+}
+
+pub async fn route_quote(params: Value, state: Arc<AppState>) -> Result<Value, String> {
+    let (request, services_to_run, skipped) = match prepare_quote(&params, &state).await? {
+        QuotePlan::Ready { request, services, skipped } => (*request, services, skipped),
+        QuotePlan::Unavailable(response) => return Ok(response),
+    };
 
     // Prepare the list of futures without spawning tasks
-    let futures = services_to_run.into_iter().map(|(name, service)| {
-        let params_clone = extended_params.clone();
-        let state_clone = Arc::clone(&state);
-        async move {
-            match timeout(Duration::from_secs(30), service(params_clone, state_clone)).await {
-                Ok(data) => match data {
-                    Ok(value) => {
-                        if validate_response_format(&value) {
-                            Some(json!({
-                                "name": name,
-                                "data": value
-                            }))
-                        } else {
-                            error!("Invalid response format from {}: {:?}", name, value);
-                            None
-                        }
-                    },
-                    Err(e) => {
-                        error!("Error in {}: {}", name, e);
-                        None
-                    }
-                },
-                Err(_) => {
-                    error!("Timeout for {}", name);
-                    None
-                }
-            }
-        }
+    let futures = services_to_run.into_iter().map(|(name, adapter)| {
+        quote_with_adapter(name, adapter, request.clone(), Arc::clone(&state))
     });
 
-    // Execute all futures concurrently
-    let results: Vec<_> = join_all(futures).await
+    // Execute all futures concurrently, keeping failures so clients can see why a dapp didn't quote
+    let (results, errors): (Vec<_>, Vec<_>) = join_all(futures).await
         .into_iter()
-        .filter_map(|res| res)
-        .collect();
-    
+        .partition(|res| res.is_ok());
+    let results: Vec<Value> = results.into_iter().filter_map(Result::ok).collect();
+    let errors: Vec<Value> = errors.into_iter().filter_map(Result::err).chain(skipped).collect();
+
+    Ok(ranked_response(results, errors, &request.params))
+}
 
+// Numbers quotes in the order they arrived, then ranks them into the final response
+pub fn ranked_response(results: Vec<Value>, errors: Vec<Value>, params: &Value) -> Value {
     if results.is_empty() {
-        return Ok(json!({
+        return json!({
             "success": false,
-            "message": "No valid quotes found."
-        }));
+            "message": "No valid quotes found.",
+            "errors": errors
+        });
     }
 
     // Sort and structure the response
@@ -163,24 +245,52 @@ pub async fn route_quote(params: Value, state: Arc<AppState>) -> Result<Value, S
         })
         .collect();
 
-    sorted_results.sort_by(|a, b| {
-        let a_amount = a["data"]["toAmount"].as_str().unwrap_or("0");
-        let b_amount = b["data"]["toAmount"].as_str().unwrap_or("0");
-        b_amount.cmp(a_amount)
-    });
+    rank_quotes(&mut sorted_results, SortBy::from_options(&params["options"]));
 
-    Ok(json!({
+    json!({
         "success": true,
-        "data": sorted_results
-    }))
+        "data": sorted_results,
+        "errors": errors,
+        // How old the cached gas prices behind swapCost were when the quote started
+        "gasDataAgeMs": params["feeData"]["ageMs"]
+    })
 }
 
-fn validate_response_format(data: &Value) -> bool {
-    data.get("tool").is_some() &&
-    data.get("fromChainId").is_some() &&
-    data.get("fromAmount").is_some() &&
-    data.get("toAmount").is_some() &&
-    data.get("fromToken").is_some() &&
-    data.get("toToken").is_some() &&
-    data.get("transaction").is_some()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+
+    fn names(dapps: &[&str]) -> Vec<String> {
+        dapps.iter().map(|dapp| dapp.to_string()).collect()
+    }
+
+    #[test]
+    fn default_set_skips_silently() {
+        let available = names(&["across", "balancer", "wowmax"]);
+        let (services, skipped) = select_services(Vec::new(), &available, TOKEN, (1, 1), true);
+
+        let running: Vec<&str> = services.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(running, vec!["balancer"]);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn explicit_dapps_report_why_they_were_skipped() {
+        let available = names(&["across", "balancer"]);
+        let requested = names(&["across", "balancer", "wowmax", "nope"]);
+        let (services, skipped) = select_services(requested, &available, TOKEN, (1, 1), false);
+
+        assert_eq!(services.len(), 1);
+        let reasons: Vec<(&str, &str)> = skipped.iter()
+            .map(|entry| (entry["name"].as_str().unwrap(), entry["message"].as_str().unwrap()))
+            .collect();
+        assert_eq!(reasons, vec![
+            ("across", "Does not support chain 1 to 1"),
+            ("wowmax", "Not enabled for 0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48 on chain 1 to 1"),
+            ("nope", "Unknown dapp"),
+        ]);
+        assert!(skipped.iter().all(|entry| entry["category"] == json!("unsupported")));
+    }
 }
@@ -6,6 +6,7 @@ use crate::services::quote_router::route_quote;
 use crate::services::quote_stream_router::route_quote_stream;
 use crate::load_resources::AppState;
 use tokio::time::{Duration, sleep};
+use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 use tokio::sync::mpsc;
 use tracing::{error, info};
@@ -24,24 +25,13 @@ pub async fn process_quote(params: Value, cache: Cache, state: Arc<AppState>) ->
         Ok(response) => {
             let request_id = Uuid::new_v4().to_string();
             
-            // Use DashMap's entry API for more efficient insertions
-            cache.entry(request_id.clone()).or_insert(response.clone());
-            state.quote_cache.entry(request_id.clone()).or_insert(response.clone());
-
-            // Spawn a task to remove the cache entry after 10 minutes
-            let cache_clone = Arc::clone(&cache);
-            let quote_cache_clone = Arc::clone(&state.quote_cache);
-            let req_id_clone = request_id.clone();
-            tokio::spawn(async move {
-                sleep(Duration::from_secs(600)).await;
-                cache_clone.remove(&req_id_clone);
-                quote_cache_clone.remove(&req_id_clone);
-            });
+            cache_response(vec![Arc::clone(&cache), Arc::clone(&state.quote_cache)], &request_id, &response);
 
             let result = json!({
                 "requestId": request_id,
                 "success": response["success"],
-                "data": response.get("data").unwrap_or(&json!([]))
+                "data": response.get("data").unwrap_or(&json!([])),
+                "errors": response.get("errors").unwrap_or(&json!([]))
             });
 
             Ok(result)
@@ -53,18 +43,73 @@ pub async fn process_quote(params: Value, cache: Cache, state: Arc<AppState>) ->
     }
 }
 
+// Keeps a ranked response for 10 minutes so its quotes can be built into transactions
+fn cache_response(caches: Vec<Cache>, request_id: &str, response: &Value) {
+    for cache in &caches {
+        // Use DashMap's entry API for more efficient insertions
+        cache.entry(request_id.to_string()).or_insert(response.clone());
+    }
+
+    // Spawn a task to remove the cache entry after 10 minutes
+    let caches_clone = caches.clone();
+    let req_id_clone = request_id.to_string();
+    tokio::spawn(async move {
+        sleep(Duration::from_secs(600)).await;
+        for cache in &caches_clone {
+            cache.remove(&req_id_clone);
+        }
+    });
+
+    // Firm quotes lapse on their own schedule, well before the whole response does
+    for quote in response["data"].as_array().into_iter().flatten() {
+        if let (Some(quote_id), Some(expires_at)) = (quote["id"].as_u64(), quote["data"]["expiresAt"].as_u64()) {
+            for cache in &caches {
+                drop_quote_at_expiry(Arc::clone(cache), request_id.to_string(), quote_id, expires_at);
+            }
+        }
+    }
+}
+
+fn drop_quote_at_expiry(cache: Cache, request_id: String, quote_id: u64, expires_at: u64) {
+    tokio::spawn(async move {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        sleep(Duration::from_secs(expires_at.saturating_sub(now))).await;
+        if let Some(mut cached) = cache.get_mut(&request_id) {
+            expire_quote(&mut cached, quote_id);
+        }
+    });
+}
+
+// Drops a lapsed quote's data but keeps a tombstone, so build-transaction can answer 410 rather than 404
+pub fn expire_quote(response: &mut Value, quote_id: u64) {
+    for quote in response["data"].as_array_mut().into_iter().flatten() {
+        if quote["id"].as_u64() == Some(quote_id) {
+            *quote = json!({ "id": quote_id, "expired": true });
+        }
+    }
+}
+
 pub async fn process_quote_stream(params: Value, state: Arc<AppState>) -> mpsc::Receiver<Result<Value, String>> {
-    println!("Processing quote stream with params: {:?}", params);
+    info!("Processing quote stream with params: {:?}", params);
 
     let (tx, rx) = mpsc::channel(100);
 
     tokio::spawn(async move {
         let mut stream = route_quote_stream(params, Arc::clone(&state)).await;
 
-        while let Some(result) = stream.recv().await {
+        while let Some(mut result) = stream.recv().await {
+            // The summary is cached like a regular quote response so its quotes can be built later
+            if let Ok(event) = &mut result {
+                if event["event"] == "summary" && event["data"]["success"].as_bool().unwrap_or(false) {
+                    let request_id = Uuid::new_v4().to_string();
+                    cache_response(vec![Arc::clone(&state.quote_cache)], &request_id, &event["data"]);
+                    event["data"]["requestId"] = json!(request_id);
+                }
+            }
+
             // Immediately forward each result as it's received
             if let Err(e) = tx.send(result).await {
-                eprintln!("Error sending result through channel: {}", e);
+                error!("Error sending result through channel: {}", e);
                 break;
             }
         }
@@ -73,14 +118,14 @@ pub async fn process_quote_stream(params: Value, state: Arc<AppState>) -> mpsc::
     rx
 }
 
-pub async fn process_transaction_from_quote(quote: &Value, state: &Arc<AppState>) -> Result<Value, String> {
+pub async fn process_transaction_from_quote(quote: &Value, permit: Option<&Value>, state: &Arc<AppState>) -> Result<Value, String> {
     info!("Processing transaction from quote with data: {:?}", quote);
 
     if !is_valid_quote(quote) {
         return Err("Quote validation failed due to missing required fields".to_string());
     }
 
-    route_transaction_from_quote(quote, state.clone()).await
+    route_transaction_from_quote(quote, permit, state.clone()).await
 }
 
 fn is_valid_quote(quote: &Value) -> bool {
@@ -0,0 +1,68 @@
+//src/services/quote_stream_router.rs
+use crate::services::quote_router::{prepare_quote, quote_with_adapter, ranked_response, QuotePlan};
+use crate::load_resources::AppState;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::debug;
+use futures::stream::{FuturesUnordered, StreamExt};
+
+// Streams `quote` events as adapters resolve, then the `error` events, then one ranked `summary`
+pub async fn route_quote_stream(params: Value, state: Arc<AppState>) -> mpsc::Receiver<Result<Value, String>> {
+    let (tx, rx) = mpsc::channel(100);
+
+    tokio::spawn(async move {
+        let (request, services_to_run, skipped) = match prepare_quote(&params, &state).await {
+            Ok(QuotePlan::Ready { request, services, skipped }) => (*request, services, skipped),
+            Ok(QuotePlan::Unavailable(response)) => {
+                let _ = tx.send(Ok(json!({ "event": "summary", "data": response }))).await;
+                return;
+            }
+            Err(e) => {
+                let _ = tx.send(Err(e)).await;
+                return;
+            }
+        };
+
+        let mut pending: FuturesUnordered<_> = services_to_run.into_iter()
+            .map(|(name, adapter)| quote_with_adapter(name, adapter, request.clone(), Arc::clone(&state)))
+            .collect();
+
+        let mut results = Vec::new();
+        // Skipped dapps are reported with the adapter failures once every adapter has answered
+        let mut errors = skipped;
+
+        while let Some(outcome) = pending.next().await {
+            match outcome {
+                Ok(result) => {
+                    // Ids follow arrival order, matching the ones ranked_response assigns
+                    let event = json!({
+                        "event": "quote",
+                        "data": {
+                            "id": results.len() + 1,
+                            "name": result["name"],
+                            "data": result["data"]
+                        }
+                    });
+                    results.push(result);
+                    if tx.send(Ok(event)).await.is_err() {
+                        debug!("Quote stream receiver dropped, cancelling remaining adapters");
+                        return;
+                    }
+                }
+                Err(error) => errors.push(error),
+            }
+        }
+
+        for error in &errors {
+            if tx.send(Ok(json!({ "event": "error", "data": error }))).await.is_err() {
+                return;
+            }
+        }
+
+        let summary = ranked_response(results, errors, &request.params);
+        let _ = tx.send(Ok(json!({ "event": "summary", "data": summary }))).await;
+    });
+
+    rx
+}
@@ -0,0 +1,278 @@
+//src/services/simulation.rs
+use ethers::abi::{decode, Abi, ParamType, Token};
+use ethers::prelude::*;
+use ethers::types::spoof;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Bytes, TransactionRequest, H256, U256};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::time::timeout;
+use crate::load_resources::AppState;
+use crate::dapps::ADAPTER_REGISTRY;
+use crate::services::approval::required_allowance;
+use crate::utils::utils::get_random_rpc_proxy_provider;
+use tracing::debug;
+
+const SIMULATION_TIMEOUT_SECS: u64 = 10;
+
+// Error(string) and Panic(uint256), the reverts every Solidity contract can produce
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+// Written into candidate storage slots to find the one a view call reads from
+const SLOT_PROBE: u64 = 0x5151_7a7e;
+
+lazy_static::lazy_static! {
+    static ref ERC20_READS_ABI: Abi = ethers::abi::parse_abi(&[
+        "function balanceOf(address) view returns (uint256)",
+        "function allowance(address,address) view returns (uint256)",
+    ]).expect("Failed to parse ERC20 ABI");
+}
+
+fn word(value: U256) -> H256 {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    H256::from(bytes)
+}
+
+// Token's Display prints numbers as bare hex and addresses without 0x, neither reads well in a revert reason
+fn format_error_arg(token: &Token) -> String {
+    match token {
+        Token::Uint(value) => value.to_string(),
+        Token::Int(value) => I256::from_raw(*value).to_string(),
+        Token::Address(address) => format!("{:?}", address),
+        Token::Array(items) | Token::FixedArray(items) => {
+            format!("[{}]", items.iter().map(format_error_arg).collect::<Vec<_>>().join(", "))
+        }
+        Token::Tuple(fields) => format!("({})", fields.iter().map(format_error_arg).collect::<Vec<_>>().join(", ")),
+        other => other.to_string(),
+    }
+}
+
+// Decodes revert data into something readable, trying the dapp's own custom errors last
+pub fn decode_revert_reason(data: &[u8], abis: &[&Abi]) -> String {
+    if data.len() < 4 {
+        return "Reverted without a reason".to_string();
+    }
+    let (selector, args) = data.split_at(4);
+
+    let first_token = |kind: ParamType| decode(&[kind], args).ok().and_then(|tokens| tokens.into_iter().next());
+    if selector == ERROR_STRING_SELECTOR {
+        if let Some(Token::String(reason)) = first_token(ParamType::String) {
+            return reason;
+        }
+    }
+    if selector == PANIC_SELECTOR {
+        if let Some(Token::Uint(code)) = first_token(ParamType::Uint(256)) {
+            return format!("Panic(0x{:x})", code);
+        }
+    }
+
+    for error in abis.iter().flat_map(|abi| abi.errors()) {
+        if error.signature().as_bytes()[..4] == *selector {
+            let inputs = error.decode(args)
+                .map(|tokens| tokens.iter().map(format_error_arg).collect::<Vec<_>>().join(", "))
+                .unwrap_or_default();
+            return format!("{}({})", error.name, inputs);
+        }
+    }
+
+    format!("Unknown revert 0x{}", hex::encode(data))
+}
+
+// The decoded reason behind a reverted eth_call or eth_estimateGas, None for transport errors
+pub fn revert_reason(error: &ProviderError, tool: &str) -> Option<String> {
+    let revert_data = RpcError::as_error_response(error)?.as_revert_data()?;
+    let abis = ADAPTER_REGISTRY.get(tool).map(|adapter| adapter.error_abis()).unwrap_or_default();
+    Some(decode_revert_reason(&revert_data, &abis))
+}
+
+// Storage layouts differ per token, so the slot is found by overriding each one the call touches
+async fn find_storage_slot(provider: &Provider<Http>, token: Address, call_data: Bytes) -> Option<H256> {
+    let call: TypedTransaction = TransactionRequest::new().to(token).data(call_data).into();
+    let access_list = provider.create_access_list(&call, None).await.ok()?.access_list;
+    let probe = H256::from_low_u64_be(SLOT_PROBE);
+
+    for item in access_list.0.iter().filter(|item| item.address == token) {
+        for key in &item.storage_keys {
+            let mut overrides = spoof::State::default();
+            overrides.account(token).store(*key, probe);
+            let Ok(result) = provider.call_raw(&call).state(&overrides).await else {
+                continue;
+            };
+            if result.len() >= 32 && H256::from_slice(&result[..32]) == probe {
+                return Some(*key);
+            }
+        }
+    }
+    None
+}
+
+// Funds fromAddress with `amount` of the input token and grants `spender` the allowance,
+// so transactions from wallets that can't pay yet still simulate
+pub async fn build_state_overrides(
+    provider: &Provider<Http>,
+    token_address: &str,
+    amount: U256,
+    spender: Option<Address>,
+    from: Address,
+    value: U256,
+) -> Result<spoof::State, String> {
+    let mut overrides = spoof::State::default();
+    overrides.account(from).balance(value.saturating_add(U256::exp10(24)));
+
+    if token_address == "0x0000000000000000000000000000000000000000" {
+        return Ok(overrides);
+    }
+
+    let token = Address::from_str(token_address).map_err(|e| format!("Invalid token address: {}", e))?;
+    let amount = word(amount);
+
+    let balance_call = ERC20_READS_ABI.function("balanceOf")
+        .and_then(|function| function.encode_input(&[Token::Address(from)]))
+        .map_err(|e| format!("Failed to encode balanceOf: {}", e))?;
+    match find_storage_slot(provider, token, Bytes::from(balance_call)).await {
+        Some(slot) => { overrides.account(token).store(slot, amount); }
+        None => debug!("No balance slot found for {:?}, simulating with the real balance", token),
+    }
+
+    if let Some(spender) = spender.filter(|address| !address.is_zero()) {
+        let allowance_call = ERC20_READS_ABI.function("allowance")
+            .and_then(|function| function.encode_input(&[Token::Address(from), Token::Address(spender)]))
+            .map_err(|e| format!("Failed to encode allowance: {}", e))?;
+        match find_storage_slot(provider, token, Bytes::from(allowance_call)).await {
+            Some(slot) => { overrides.account(token).store(slot, amount); }
+            None => debug!("No allowance slot found for {:?}, simulating with the real allowance", token),
+        }
+    }
+
+    Ok(overrides)
+}
+
+// eth_call of the quote's transaction against overridden state, as {ok, gasUsed, revertReason}
+pub async fn simulate_transaction(quote: &Value, state: &Arc<AppState>) -> Result<Value, String> {
+    let tool = quote["tool"].as_str().unwrap_or_default();
+    let chain_id = quote["fromChainId"].as_u64().ok_or("Invalid fromChainId")?;
+    let transaction = &quote["transaction"];
+
+    let data = transaction["data"].as_str()
+        .filter(|data| data.starts_with("0x"))
+        .ok_or("No calldata to simulate")?;
+    let data = Bytes::from_str(data).map_err(|e| format!("Invalid calldata: {}", e))?;
+    let to = Address::from_str(transaction["to"].as_str().unwrap_or_default())
+        .map_err(|e| format!("Invalid to address: {}", e))?;
+    let from = Address::from_str(quote["fromAddress"].as_str().unwrap_or_default())
+        .map_err(|e| format!("Invalid from address: {}", e))?;
+    let value = U256::from_dec_str(transaction["value"].as_str().unwrap_or("0"))
+        .map_err(|e| format!("Invalid value: {}", e))?;
+
+    let provider = get_random_rpc_proxy_provider(chain_id, &state.rpc_proxy_providers)
+        .ok_or_else(|| format!("No RPC provider found for chain ID: {}", chain_id))?;
+
+    let token_address = quote["fromToken"]["address"].as_str().ok_or("Missing fromToken address")?;
+    let spender = quote["approvalAddress"].as_str().and_then(|address| Address::from_str(address).ok());
+    let overrides = build_state_overrides(&provider, token_address, required_allowance(quote)?, spender, from, value).await?;
+    let call: TypedTransaction = TransactionRequest::new().from(from).to(to).value(value).data(data).into();
+
+    match provider.call_raw(&call).state(&overrides).await {
+        Ok(_) => {
+            // Not every RPC takes overrides on eth_estimateGas, gasUsed is left empty when it doesn't
+            let gas_used = provider.request::<_, U256>("eth_estimateGas", (&call, "latest", &overrides)).await
+                .map_err(|e| debug!("eth_estimateGas with overrides failed for {}: {}", tool, e))
+                .ok();
+            Ok(json!({
+                "ok": true,
+                "gasUsed": gas_used.map(|gas| gas.to_string()),
+                "revertReason": null
+            }))
+        }
+        Err(e) => match revert_reason(&e, tool) {
+            Some(reason) => Ok(json!({
+                "ok": false,
+                "gasUsed": null,
+                "revertReason": reason
+            })),
+            None => Err(format!("Simulation call failed: {}", e)),
+        },
+    }
+}
+
+// Simulation is advisory, quotes go out without it when the RPC can't run one.
+// `budget` is what's left of the caller's timeout, so simulating never delays the response past it
+pub async fn attach_simulation(quote: &mut Value, budget: Duration, state: &Arc<AppState>) {
+    // Quote-only data has nothing to run, and a quote whose gas we estimated already carries its simulation
+    if quote["transaction"]["data"].as_str() == Some("quote") || quote.get("simulation").is_some() {
+        return;
+    }
+
+    let budget = budget.min(Duration::from_secs(SIMULATION_TIMEOUT_SECS));
+    match timeout(budget, simulate_transaction(quote, state)).await {
+        Ok(Ok(simulation)) => quote["simulation"] = simulation,
+        Ok(Err(e)) => debug!("Skipping simulation for {}: {}", quote["tool"], e),
+        Err(_) => debug!("Simulation for {} timed out after {}ms", quote["tool"], budget.as_millis()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::encode;
+
+    fn revert_data(selector: [u8; 4], tokens: &[Token]) -> Vec<u8> {
+        let mut data = selector.to_vec();
+        data.extend(encode(tokens));
+        data
+    }
+
+    #[test]
+    fn decodes_error_string() {
+        let data = revert_data(ERROR_STRING_SELECTOR, &[Token::String("Too little received".to_string())]);
+        assert_eq!(decode_revert_reason(&data, &[]), "Too little received");
+    }
+
+    #[test]
+    fn decodes_panic_code() {
+        let data = revert_data(PANIC_SELECTOR, &[Token::Uint(U256::from(0x11u64))]);
+        assert_eq!(decode_revert_reason(&data, &[]), "Panic(0x11)");
+    }
+
+    #[test]
+    fn decodes_custom_errors_from_the_dapp_abi() {
+        let abi = ethers::abi::parse_abi(&["error MaxTransferSizeExceeded()", "error TooLittleReceived(uint256,uint256)"]).unwrap();
+        let error = abi.error("TooLittleReceived").unwrap();
+        let mut data = error.signature().as_bytes()[..4].to_vec();
+        data.extend(encode(&[Token::Uint(U256::from(990u64)), Token::Uint(U256::from(1_000u64))]));
+        assert_eq!(decode_revert_reason(&data, &[&abi]), "TooLittleReceived(990, 1000)");
+
+        let signature = abi.error("MaxTransferSizeExceeded").unwrap().signature();
+        assert_eq!(decode_revert_reason(&signature.as_bytes()[..4], &[&abi]), "MaxTransferSizeExceeded()");
+    }
+
+    #[test]
+    fn formats_signed_and_address_error_args() {
+        let abi = ethers::abi::parse_abi(&["error BadDelta(int256,address)"]).unwrap();
+        let error = abi.error("BadDelta").unwrap();
+        let recipient = Address::from_low_u64_be(0xab);
+        let mut data = error.signature().as_bytes()[..4].to_vec();
+        data.extend(encode(&[Token::Int(I256::from(-5).into_raw()), Token::Address(recipient)]));
+        assert_eq!(
+            decode_revert_reason(&data, &[&abi]),
+            "BadDelta(-5, 0x00000000000000000000000000000000000000ab)"
+        );
+    }
+
+    #[test]
+    fn reports_short_and_unknown_revert_data() {
+        assert_eq!(decode_revert_reason(&[], &[]), "Reverted without a reason");
+        assert_eq!(decode_revert_reason(&[0x08, 0xc3, 0x79], &[]), "Reverted without a reason");
+        assert_eq!(decode_revert_reason(&[0xde, 0xad, 0xbe, 0xef, 0x01], &[]), "Unknown revert 0xdeadbeef01");
+    }
+
+    #[test]
+    fn malformed_error_string_falls_through_to_unknown() {
+        let data = [ERROR_STRING_SELECTOR.as_slice(), &[0x01]].concat();
+        assert_eq!(decode_revert_reason(&data, &[]), "Unknown revert 0x08c379a001");
+    }
+}
@@ -0,0 +1,92 @@
+//src/services/transaction_router.rs
+use crate::services::quote_router::{prepare_quote, QuotePlan};
+use crate::services::approval::generate_approval_transaction;
+use crate::services::permit::{prepare_permit, verify_permit};
+use crate::services::calldata_verifier::verify_quote;
+use crate::services::simulation::attach_simulation;
+use crate::load_resources::AppState;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+use tracing::{debug, error};
+
+// Rebuilds the request params a cached quote was produced from, limited to the quote's dapp
+fn request_params_from_quote(quote: &Value) -> Result<Value, String> {
+    let tool = quote["tool"].as_str().ok_or("Invalid tool")?;
+    let mut options = quote["options"].clone();
+    options["dapps"] = json!([tool]);
+
+    // Exact-output quotes are rebuilt for the same output, exact-input ones for the same input
+    let exact_out = quote.get("fromAmountMax").is_some();
+    let (amount, to_amount) = if exact_out {
+        (json!(""), quote["toAmount"].clone())
+    } else {
+        (quote.get("requestedAmount").unwrap_or(&quote["fromAmount"]).clone(), Value::Null)
+    };
+
+    Ok(json!({
+        "fromChainId": quote["fromChainId"],
+        "fromAddress": quote["fromAddress"],
+        "amount": amount,
+        "toAmount": to_amount,
+        "fromTokenAddress": quote["fromToken"]["address"],
+        "toTokenAddress": quote["toToken"]["address"],
+        "toAddress": quote["toAddress"],
+        "toChainId": quote["toChainId"],
+        "options": options
+    }))
+}
+
+// Re-runs the quote's adapter with quoteOnly off, so the calldata and gas estimate are fresh
+pub async fn route_transaction_from_quote(quote: &Value, permit: Option<&Value>, state: Arc<AppState>) -> Result<Value, String> {
+    let params = request_params_from_quote(quote)?;
+    debug!("Building transaction with params: {:?}", params);
+
+    let (mut request, services) = match prepare_quote(&params, &state).await? {
+        QuotePlan::Ready { request, services, .. } => (*request, services),
+        QuotePlan::Unavailable(response) => {
+            return Err(response["message"].as_str().unwrap_or("No dApps available").to_string());
+        }
+    };
+
+    let (name, adapter) = services.into_iter().next()
+        .ok_or("The quoted dapp is no longer available for this route")?;
+
+    request.quote_only = false;
+    request.params["quoteOnly"] = json!(false);
+
+    // With approve "permit" the wallet signs typed data first, and the signature comes back on a second call
+    let permit_kinds = adapter.permits_for(request.from_chain_id, &request.from_token_address);
+    if let Some(permit) = permit {
+        request.permit = Some(verify_permit(quote, permit, &permit_kinds, &state).await?);
+    } else if quote["options"]["approve"].as_str() == Some("permit") && !permit_kinds.is_empty() {
+        if let Some(permit_request) = prepare_permit(quote, &permit_kinds, &state).await? {
+            return Ok(permit_request);
+        }
+    }
+    let has_permit = request.permit.is_some();
+
+    let started = Instant::now();
+    let response = match timeout(Duration::from_secs(30), adapter.quote(request, Arc::clone(&state))).await {
+        Ok(Ok(response)) => response,
+        Ok(Err(e)) => {
+            error!("Error building transaction with {} ({:?}): {}", name, e.kind, e);
+            return Err(e.message);
+        }
+        Err(_) => return Err(format!("{} timed out after 30s", name)),
+    };
+
+    let mut transaction = serde_json::to_value(response).map_err(|e| format!("Failed to serialize quote: {}", e))?;
+    verify_quote(&mut transaction)?;
+    attach_simulation(&mut transaction, Duration::from_secs(30).saturating_sub(started.elapsed()), &state).await;
+
+    // The swap can only execute once the router may pull the input token, which a permit already grants
+    if !has_permit {
+        if let Some(approval) = generate_approval_transaction(&transaction, &state).await? {
+            transaction["approvalTransaction"] = approval;
+        }
+    }
+
+    Ok(transaction)
+}
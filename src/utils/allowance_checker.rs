@@ -1,4 +1,4 @@
-// src/utils/allowance_check.rs
+// src/utils/allowance_checker.rs
 use ethers::prelude::*;
 use ethers::types::{Address, U256};
 use serde_json::Value;
@@ -6,7 +6,6 @@ use std::sync::Arc;
 use std::str::FromStr;
 use crate::load_resources::AppState;
 use crate::utils::utils::get_random_rpc_proxy_provider;
-use tracing::error;
 
 // ERC20 ABI for allowance function
 const TOKEN_ABI: &str = r#"[
@@ -33,24 +32,23 @@ const TOKEN_ABI: &str = r#"[
     }
 ]"#;
 
-pub async fn check_user_allowance(quote: &Value, state: &Arc<AppState>) -> Result<bool, String> {
+// Current allowance the quote's approval address has on the user's fromToken, None for native tokens
+pub async fn get_user_allowance(quote: &Value, state: &Arc<AppState>) -> Result<Option<U256>, String> {
     let approval_address = quote["approvalAddress"].as_str().ok_or("Missing approvalAddress")?;
     let from_token = &quote["fromToken"];
-    let transaction = &quote["transaction"];
-    let from_amount = quote["fromAmount"].as_str().ok_or("Missing fromAmount")?;
     let chain_id = from_token["chainId"].as_u64().ok_or("Invalid chainId")?;
 
     let from_token_address = from_token["address"].as_str().ok_or("Missing fromToken address")?;
     
     // Check if fromToken.address is zero address (native token)
     if from_token_address == "0x0000000000000000000000000000000000000000" {
-        return Ok(true); // Assume native token always has enough allowance
+        return Ok(None); // Native tokens are sent as value and need no allowance
     }
 
     let provider = get_random_rpc_proxy_provider(chain_id, &state.rpc_proxy_providers)
         .ok_or_else(|| format!("No RPC provider found for chain ID: {}", chain_id))?;
 
-    let from_address = transaction["from"].as_str().ok_or("Missing from address")?;
+    let from_address = quote["fromAddress"].as_str().ok_or("Missing from address")?;
     let approval_address = Address::from_str(approval_address)
         .map_err(|e| format!("Invalid approval address: {}", e))?;
     let token_address = Address::from_str(from_token_address)
@@ -61,10 +59,7 @@ pub async fn check_user_allowance(quote: &Value, state: &Arc<AppState>) -> Resul
     let allowance: U256 = get_allowance(&provider, token_address, owner, approval_address).await
         .map_err(|e| format!("Failed to get allowance: {}", e))?;
 
-    let required_amount = U256::from_dec_str(from_amount)
-        .map_err(|e| format!("Invalid fromAmount: {}", e))?;
-
-    Ok(allowance >= required_amount)
+    Ok(Some(allowance))
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -0,0 +1,188 @@
+// src/utils/fee_oracle.rs
+use ethers::prelude::*;
+use ethers::types::{BlockNumber, FeeHistory, U256};
+use ethers::utils::format_units;
+use serde_json::{Map, Value, json};
+use std::sync::Arc;
+use crate::load_resources::AppState;
+use crate::utils::utils::{fetch_gas_price, get_random_rpc_proxy_provider};
+use tracing::debug;
+
+// Recent blocks the priority fee percentiles are taken over
+const FEE_HISTORY_BLOCKS: u64 = 10;
+
+// Priority fee percentile for each speed tier
+const TIER_PERCENTILES: [(&str, f64); 3] = [("slow", 10.0), ("normal", 50.0), ("fast", 90.0)];
+
+#[derive(Debug, Clone)]
+pub struct FeeTier {
+    pub name: &'static str,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    // What the transaction pays per gas at the current base fee, used for swap costs
+    pub effective_gas_price: U256,
+}
+
+#[derive(Debug, Clone)]
+pub struct FeeEstimate {
+    // Legacy gasPrice, or the normal tier's effective price on EIP-1559 chains
+    pub gas_price: U256,
+    // None on chains without a base fee
+    pub base_fee: Option<U256>,
+    pub tiers: Vec<FeeTier>,
+}
+
+impl FeeEstimate {
+    fn legacy(gas_price: U256) -> Self {
+        FeeEstimate {
+            gas_price,
+            base_fee: None,
+            tiers: Vec::new(),
+        }
+    }
+
+    pub fn is_legacy(&self) -> bool {
+        self.base_fee.is_none()
+    }
+
+    // [wei, gwei], the shape adapters read from params.gasPrices
+    pub fn gas_prices(&self) -> (String, String) {
+        let gas_price_gwei = format_units(self.gas_price, "gwei").unwrap_or_else(|_| "0".to_string());
+        (self.gas_price.to_string(), gas_price_gwei)
+    }
+
+    // Passed to format_swap_details as params.feeData
+    pub fn to_json(&self) -> Value {
+        let tiers: Map<String, Value> = self.tiers.iter()
+            .map(|tier| (tier.name.to_string(), json!({
+                "maxFeePerGas": tier.max_fee_per_gas.to_string(),
+                "maxPriorityFeePerGas": tier.max_priority_fee_per_gas.to_string(),
+                "effectiveGasPrice": tier.effective_gas_price.to_string()
+            })))
+            .collect();
+
+        json!({
+            "type": if self.is_legacy() { "legacy" } else { "eip1559" },
+            "gasPrice": self.gas_price.to_string(),
+            "baseFeePerGas": self.base_fee.map(|fee| fee.to_string()),
+            "tiers": tiers
+        })
+    }
+}
+
+fn median(mut values: Vec<U256>) -> U256 {
+    values.sort();
+    values.get(values.len() / 2).copied().unwrap_or_default()
+}
+
+// None when the history shows no base fee, i.e. the chain still prices gas the legacy way
+fn eip1559_estimate(history: &FeeHistory) -> Option<FeeEstimate> {
+    // The last entry is the base fee of the block being built next
+    let next_base_fee = history.base_fee_per_gas.last().copied().filter(|fee| !fee.is_zero())?;
+
+    let tiers: Vec<FeeTier> = TIER_PERCENTILES.iter().enumerate()
+        .map(|(index, (name, _))| {
+            let priority_fee = median(history.reward.iter().filter_map(|rewards| rewards.get(index).copied()).collect());
+            FeeTier {
+                name,
+                // Doubling the base fee keeps the transaction valid through several full blocks
+                max_fee_per_gas: next_base_fee * 2 + priority_fee,
+                max_priority_fee_per_gas: priority_fee,
+                effective_gas_price: next_base_fee + priority_fee,
+            }
+        })
+        .collect();
+
+    let gas_price = tiers.iter().find(|tier| tier.name == "normal")?.effective_gas_price;
+
+    Some(FeeEstimate {
+        gas_price,
+        base_fee: Some(next_base_fee),
+        tiers,
+    })
+}
+
+// Speed tiers from eth_feeHistory, falling back to eth_gasPrice on chains without EIP-1559
+pub async fn fetch_fee_estimate(chain_id: u64, state: &Arc<AppState>) -> Result<FeeEstimate, String> {
+    if let Some(provider) = get_random_rpc_proxy_provider(chain_id, &state.rpc_proxy_providers) {
+        let percentiles: Vec<f64> = TIER_PERCENTILES.iter().map(|(_, percentile)| *percentile).collect();
+        match provider.fee_history(FEE_HISTORY_BLOCKS, BlockNumber::Latest, &percentiles).await {
+            Ok(history) => match eip1559_estimate(&history) {
+                Some(estimate) => return Ok(estimate),
+                None => debug!("Chain {} reports no base fee, using legacy gasPrice", chain_id),
+            },
+            Err(e) => debug!("eth_feeHistory failed on chain {}, using legacy gasPrice: {}", chain_id, e),
+        }
+    }
+
+    let (gas_price_wei, _) = fetch_gas_price(chain_id, Arc::clone(state)).await
+        .map_err(|e| format!("Failed to fetch gas price: {}", e))?;
+    let gas_price = U256::from_dec_str(&gas_price_wei).map_err(|e| format!("Invalid gas price: {}", e))?;
+
+    Ok(FeeEstimate::legacy(gas_price))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gwei(value: u64) -> U256 {
+        U256::from(value) * U256::exp10(9)
+    }
+
+    fn history(base_fees: &[u64], rewards: &[[u64; 3]]) -> FeeHistory {
+        FeeHistory {
+            base_fee_per_gas: base_fees.iter().map(|fee| gwei(*fee)).collect(),
+            gas_used_ratio: vec![0.5; rewards.len()],
+            oldest_block: U256::from(100u64),
+            reward: rewards.iter().map(|tier| tier.iter().map(|fee| gwei(*fee)).collect()).collect(),
+        }
+    }
+
+    #[test]
+    fn median_takes_the_upper_middle_and_defaults_to_zero() {
+        assert_eq!(median(vec![U256::from(3u64), U256::from(1u64), U256::from(2u64)]), U256::from(2u64));
+        assert_eq!(median(vec![U256::from(4u64), U256::from(1u64), U256::from(3u64), U256::from(2u64)]), U256::from(3u64));
+        assert_eq!(median(Vec::new()), U256::zero());
+    }
+
+    #[test]
+    fn tiers_use_the_median_reward_per_percentile_over_the_next_base_fee() {
+        let history = history(&[20, 25, 30], &[[1, 2, 5], [3, 4, 9], [2, 3, 7]]);
+        let estimate = eip1559_estimate(&history).unwrap();
+
+        assert_eq!(estimate.base_fee, Some(gwei(30)));
+        let tier = |name: &str| estimate.tiers.iter().find(|tier| tier.name == name).unwrap();
+        for (name, priority_fee) in [("slow", 2), ("normal", 3), ("fast", 7)] {
+            assert_eq!(tier(name).max_priority_fee_per_gas, gwei(priority_fee));
+            assert_eq!(tier(name).max_fee_per_gas, gwei(60 + priority_fee));
+            assert_eq!(tier(name).effective_gas_price, gwei(30 + priority_fee));
+        }
+        assert_eq!(estimate.gas_price, gwei(33));
+    }
+
+    #[test]
+    fn blocks_missing_a_reward_are_left_out_of_the_median() {
+        let mut history = history(&[10, 10], &[[1, 2, 3]]);
+        history.reward.push(vec![gwei(5)]);
+
+        let estimate = eip1559_estimate(&history).unwrap();
+        assert_eq!(estimate.tiers[0].max_priority_fee_per_gas, gwei(5));
+        assert_eq!(estimate.tiers[2].max_priority_fee_per_gas, gwei(3));
+    }
+
+    #[test]
+    fn chains_without_a_base_fee_are_legacy() {
+        assert!(eip1559_estimate(&history(&[0, 0], &[[1, 2, 3]])).is_none());
+        assert!(eip1559_estimate(&history(&[], &[])).is_none());
+    }
+
+    #[test]
+    fn fee_data_json_lists_every_tier() {
+        let fee_data = eip1559_estimate(&history(&[10, 12], &[[1, 2, 3]])).unwrap().to_json();
+        assert_eq!(fee_data["type"], json!("eip1559"));
+        assert_eq!(fee_data["baseFeePerGas"], json!(gwei(12).to_string()));
+        assert_eq!(fee_data["tiers"]["fast"]["maxFeePerGas"], json!(gwei(27).to_string()));
+        assert_eq!(FeeEstimate::legacy(gwei(5)).to_json()["type"], json!("legacy"));
+    }
+}
@@ -7,6 +7,7 @@ use serde::{Serialize, Deserialize};
 use serde_json::Value;
 use crate::load_resources::AppState;
 use crate::utils::utils::get_random_rpc_proxy_provider;
+use crate::utils::token_catalogue::catalogue_price_usd;
 use std::path::Path;
 use std::fs::OpenOptions;
 use serde::de::{self, Deserializer}; // Import the Deserializer trait from serde::de
@@ -119,6 +120,16 @@ pub async fn fetch_token_details(
         }
     }
 
+    // Fall back to the bundled Layerswap catalogue for tokens without a price
+    for (idx, token_info) in fetched_tokens.iter_mut().enumerate() {
+        if let Some(token_info) = token_info {
+            if token_info.price_usd.is_none() {
+                let (token_address, chain_id) = tokens_with_chain_ids[idx];
+                token_info.price_usd = catalogue_price_usd(chain_id, token_address);
+            }
+        }
+    }
+
     Ok(fetched_tokens)  // Return all found tokens, including None for missing tokens
 }
 
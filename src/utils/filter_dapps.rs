@@ -1,4 +1,5 @@
 use crate::load_resources::AppState;
+use crate::dapps::ADAPTER_REGISTRY;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -67,17 +68,23 @@ pub fn filter_dapps(
                 })
                 .unwrap_or(false);
 
+            // Dapps that ship their own chain list don't need it repeated in dappConfig
+            let bundled_chain_ids = ADAPTER_REGISTRY.get(name)
+                .and_then(|adapter| adapter.capabilities().bundled_chain_ids().cloned());
+
             // Resolve chain IDs from names or IDs
             let from_chain_ids = config
                 .get("fromChainIds")
                 .and_then(|v| v.as_array())
                 .map(|ids| resolve_chain_ids(ids, &chain_name_to_id_map))
+                .or_else(|| bundled_chain_ids.clone())
                 .unwrap_or_default();
 
             let to_chain_ids = config
                 .get("toChainIds")
                 .and_then(|v| v.as_array())
                 .map(|ids| resolve_chain_ids(ids, &chain_name_to_id_map))
+                .or_else(|| bundled_chain_ids.clone())
                 .unwrap_or_default();
 
             // Check if "all" is present, meaning support for any chain
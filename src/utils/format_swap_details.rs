@@ -6,10 +6,53 @@ use std::sync::Arc;
 use std::str::FromStr;
 use crate::load_resources::AppState;
 use crate::utils::utils::get_random_rpc_proxy_provider;
-use tracing::debug;
+use crate::services::simulation::{build_state_overrides, revert_reason};
+use crate::utils::rollup_fee::fetch_l1_data_fee;
+use crate::dapps::adapter::{QuoteError, QuoteResponse};
+use tracing::{debug, error};
 
+// Gas limit for built swaps that can't be estimated, so the swap and its approval still go out
+const SWAP_GAS_FALLBACK: u64 = 500_000;
 
-async fn estimate_gas_limit(chain_id: u64, transaction: &Value, state: &Arc<AppState>) -> Result<Option<U256>, String> {
+// Why a transaction couldn't be estimated
+enum GasEstimateError {
+    // The transaction reverts even with the input funded and approved, with its decoded reason
+    Reverted(String),
+    Failed(String),
+}
+
+impl std::fmt::Display for GasEstimateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GasEstimateError::Reverted(reason) => write!(f, "reverted: {}", reason),
+            GasEstimateError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<String> for GasEstimateError {
+    fn from(e: String) -> Self {
+        GasEstimateError::Failed(e)
+    }
+}
+
+impl From<&str> for GasEstimateError {
+    fn from(e: &str) -> Self {
+        GasEstimateError::Failed(e.to_string())
+    }
+}
+
+// Estimates with fromAddress funded with `amount` of the input token and `spender` approved for it,
+// so wallets that haven't approved or funded the swap yet still get a gas limit
+async fn estimate_gas_limit(
+    tool: &str,
+    chain_id: u64,
+    transaction: &Value,
+    token_address: &str,
+    amount: U256,
+    spender: Option<Address>,
+    state: &Arc<AppState>,
+) -> Result<Option<U256>, GasEstimateError> {
     if transaction.as_object().unwrap().values().any(|v| v.as_str() == Some("quote")) {
         return Ok(None);
     }
@@ -17,11 +60,14 @@ async fn estimate_gas_limit(chain_id: u64, transaction: &Value, state: &Arc<AppS
     let provider = get_random_rpc_proxy_provider(chain_id, &state.rpc_proxy_providers)
         .ok_or("No provider available")?;
 
+    let from = Address::from_str(transaction["from"].as_str().unwrap_or_default()).map_err(|e| format!("Invalid from address: {}", e))?;
+    let value = U256::from_dec_str(transaction["value"].as_str().unwrap_or("0")).map_err(|e| format!("Invalid value: {}", e))?;
+
     // Convert the JSON transaction to a TransactionRequest
     let tx_request = TransactionRequest {
-        from: Some(Address::from_str(transaction["from"].as_str().unwrap_or_default()).map_err(|e| format!("Invalid from address: {}", e))?),
+        from: Some(from),
         to: Some(NameOrAddress::Address(Address::from_str(transaction["to"].as_str().unwrap_or_default()).map_err(|e| format!("Invalid to address: {}", e))?)),
-        value: Some(U256::from_dec_str(transaction["value"].as_str().unwrap_or("0")).map_err(|e| format!("Invalid value: {}", e))?),
+        value: Some(value),
         data: Some(Bytes::from_str(transaction["data"].as_str().unwrap_or("0x")).map_err(|e| format!("Invalid data: {}", e))?),
         nonce: None,
         gas: Some(U256::from_dec_str(transaction["gas"].as_str().unwrap_or("0")).map_err(|e| format!("Invalid gas: {}", e))?),
@@ -32,15 +78,43 @@ async fn estimate_gas_limit(chain_id: u64, transaction: &Value, state: &Arc<AppS
     // Create a TypedTransaction::Legacy
     let typed_tx = TypedTransaction::Legacy(tx_request);
 
-    match provider.estimate_gas(&typed_tx, None).await {
+    let overrides = build_state_overrides(&provider, token_address, amount, spender, from, value).await?;
+    let estimate = match provider.request::<_, U256>("eth_estimateGas", (&typed_tx, "latest", &overrides)).await {
+        // Not every RPC takes overrides on eth_estimateGas, those estimate against the real state
+        Err(e) if revert_reason(&e, tool).is_none() => {
+            debug!("eth_estimateGas with overrides failed for {}, retrying without: {}", tool, e);
+            provider.estimate_gas(&typed_tx, None).await
+        }
+        estimate => estimate,
+    };
+
+    match estimate {
         Ok(gas) => Ok(Some(gas)),
         Err(e) => {
-            eprintln!("Error estimating gas with proxy: {}", e);
-            Ok(None)
+            error!("Error estimating gas with proxy: {}", e);
+            // Keep the decoded revert so the quote says why the transaction would fail
+            Err(match revert_reason(&e, tool) {
+                Some(reason) => GasEstimateError::Reverted(reason),
+                None => GasEstimateError::Failed(e.to_string()),
+            })
         }
     }
 }
 
+// Gas reported by the adapter; the "quote" sentinel means the adapter doesn't know it
+fn adapter_gas_estimate(gas_estimate: Option<&str>) -> Option<U256> {
+    gas_estimate
+        .filter(|gas| *gas != "quote")
+        .and_then(|gas| U256::from_dec_str(gas).ok())
+}
+
+// Upper bound on the input of an exact-output swap, with slippage applied upward and rounded up
+pub fn max_amount_in(amount: U256, slippage_percent: f64) -> U256 {
+    let slippage_bps = U256::from((slippage_percent.max(0.0) * 100.0).round() as u64);
+    let scaled = amount * (U256::from(10_000u64) + slippage_bps);
+    (scaled + U256::from(9_999u64)) / U256::from(10_000u64)
+}
+
 pub async fn format_swap_details(
     tool: &str,
     params: &Value,
@@ -52,58 +126,105 @@ pub async fn format_swap_details(
     additional_fee: Option<&Value>,
     dapp_options: Option<&Value>,
     state: &Arc<AppState>
-) -> Result<Value, String> {
+) -> Result<QuoteResponse, QuoteError> {
     debug!("tool: {}, params: {:?}, transaction: {:?}, to_amount: {:?}, approval_address: {:?}, gas_data: {:?}, gas_estimate: {:?}, additional_fee: {:?}, dapp_options: {:?}",
            tool, params, transaction, to_amount, approval_address, gas_data, gas_estimate, additional_fee, dapp_options);
 
     let from_chain_id = params["fromChainId"].as_u64().ok_or("Invalid fromChainId")?;
-    let amount = params["amount"].as_str().ok_or("Invalid amount")?;
+    // Exact-output adapters report the input they quoted, since the request only fixes toAmount.
+    // Bridges that add their expenses on top of the amount report the total the transaction pulls
+    let exact_out = params["toAmount"].as_str().is_some_and(|a| !a.is_empty());
+    let requested_amount = params["amount"].as_str().filter(|_| !exact_out);
+    let amount = match dapp_options.and_then(|o| o["fromAmount"].as_str()) {
+        Some(from_amount) => from_amount,
+        None if exact_out => return Err("Missing fromAmount for exact-output quote".into()),
+        None => requested_amount.ok_or("Invalid amount")?,
+    };
     let to_address = params["toAddress"].as_str().ok_or("Invalid toAddress")?;
     let to_chain_id = params["toChainId"].as_u64().unwrap_or(from_chain_id);
     let options = params["options"].as_object().ok_or("Invalid options")?;
     let from_address = params["fromAddress"].as_str().ok_or("Invalid fromAddress")?;
+    let quote_only = params["quoteOnly"].as_bool().unwrap_or(false);
 
     let mut tx = transaction.clone();
 
+    // Use token details from params
+    let from_token_details = params["fromTokenDetails"].as_object().ok_or("Missing fromTokenDetails")?;
+    let to_token_details = params["toTokenDetails"].as_object().ok_or("Missing toTokenDetails")?;
+    let native_token_details = params["nativeTokenDetails"].as_object().ok_or("Missing nativeTokenDetails")?; // Get the nativeTokenDetails
+
+    let slippage = options["slippage"].as_f64().unwrap_or(1.0) / 100.0;
+    let from_amount = U256::from_dec_str(amount).map_err(|e| format!("Invalid amount: {}", e))?;
+
+    // Gas is estimated as if the wallet already held and approved the most the swap can pull
+    let required_amount = if exact_out { max_amount_in(from_amount, slippage * 100.0) } else { from_amount };
+    let spender = approval_address.as_str().and_then(|address| Address::from_str(address).ok());
+    let mut simulation = None;
+
     // Handle gas estimation first, as it's the most time-consuming part
     let needs_gas_estimate = gas_estimate.is_none() && !transaction.as_object().unwrap().values().any(|v| v.as_str() == Some("quote"));
     let estimated_gas = if needs_gas_estimate {
-        match estimate_gas_limit(from_chain_id, &tx, state).await {
+        let token_address = from_token_details.get("address").and_then(|a| a.as_str()).unwrap_or_default();
+        match estimate_gas_limit(tool, from_chain_id, &tx, token_address, required_amount, spender, state).await {
             Ok(Some(gas)) => {
+                // The estimate already ran against the overridden state, so it doubles as the simulation
+                simulation = Some(json!({
+                    "ok": true,
+                    "gasUsed": gas.to_string(),
+                    "revertReason": null
+                }));
                 let adjusted_gas: U256 = gas * 3 / 2;
                 tx["gas"] = json!(adjusted_gas.as_u64());
                 Some(adjusted_gas)
             },
             Ok(None) => None,
-            Err(e) => {
-                eprintln!("Error estimating gas limit: {}", e);
+            // A built swap still goes out, its simulation then reports why it would fail
+            Err(e) if !quote_only => {
+                debug!("Gas estimation failed for {}, using fallback limit: {}", tool, e);
+                tx["gas"] = json!(SWAP_GAS_FALLBACK);
+                Some(U256::from(SWAP_GAS_FALLBACK))
+            },
+            // The quote still goes out with unknown gas, flagged so clients and ranking can see it would revert
+            Err(GasEstimateError::Reverted(reason)) => {
+                debug!("Quote from {} reverts in simulation: {}", tool, reason);
+                simulation = Some(json!({
+                    "ok": false,
+                    "gasUsed": null,
+                    "revertReason": reason
+                }));
                 None
+            },
+            Err(GasEstimateError::Failed(e)) => {
+                error!("Error estimating gas limit: {}", e);
+                return Err(QuoteError::gas_estimation(format!("Gas estimation failed: {}", e)));
             }
         }
     } else {
-        gas_estimate.map(|g| U256::from_dec_str(g).unwrap_or_default())
+        adapter_gas_estimate(gas_estimate)
     };
 
-    if needs_gas_estimate && estimated_gas.is_none() {
-        eprintln!("Gas estimation failed, aborting transaction formatting.");
-        return Ok(json!(null));
+    if needs_gas_estimate && estimated_gas.is_none() && simulation.is_none() {
+        error!("Gas estimation failed, aborting transaction formatting.");
+        return Err(QuoteError::gas_estimation("Gas estimation failed"));
     }
 
-    // Use token details from params
-    let from_token_details = params["fromTokenDetails"].as_object().ok_or("Missing fromTokenDetails")?;
-    let to_token_details = params["toTokenDetails"].as_object().ok_or("Missing toTokenDetails")?;
-    let native_token_details = params["nativeTokenDetails"].as_object().ok_or("Missing nativeTokenDetails")?; // Get the nativeTokenDetails
-
-    let slippage = options["slippage"].as_f64().unwrap_or(1.0) / 100.0;
-    let from_amount = U256::from_dec_str(amount).map_err(|e| format!("Invalid amount: {}", e))?;
-
     let gas_price_wei = U256::from_dec_str(gas_data[0].as_str().unwrap_or("0"))
         .map_err(|e| format!("Invalid gas price: {}", e))?;
     let gas_gwei = gas_data[1].as_str().unwrap_or("none");
     let gas_estimated = estimated_gas.map_or("0".to_string(), |gas| gas.to_string());
 
+    // Rollups charge for posting the calldata to L1 on top of L2 execution gas
+    let l1_data_fee = if estimated_gas.is_some() {
+        fetch_l1_data_fee(from_chain_id, &tx, &params["feeData"], gas_price_wei, state).await.unwrap_or_else(|e| {
+            debug!("L1 data fee unavailable for {} on chain {}: {}", tool, from_chain_id, e);
+            None
+        })
+    } else {
+        None
+    };
+
     let swap_cost_eth = estimated_gas.map_or("none".to_string(), |gas| {
-        format!("{:.8}", (gas * gas_price_wei).as_u128() as f64 / 1e18)
+        format!("{:.8}", (gas * gas_price_wei + l1_data_fee.unwrap_or_default()).as_u128() as f64 / 1e18)
     });
 
     let from_amount_usd = from_token_details["priceUSD"].as_f64().map(|price| {
@@ -112,7 +233,7 @@ pub async fn format_swap_details(
     }).unwrap_or_else(|| "none".to_string());
 
     let to_amount_value = U256::from_dec_str(to_amount.as_str().unwrap_or("0")).unwrap_or_default();
-    let to_amount_min: U256 = if dapp_options.and_then(|o| o["noSlippage"].as_bool()).unwrap_or(false) {
+    let to_amount_min: U256 = if exact_out || dapp_options.and_then(|o| o["noSlippage"].as_bool()).unwrap_or(false) {
         to_amount_value
     } else if to_amount.as_str() != Some("none") {
         let min_amount = (to_amount_value.as_u128() as f64 * (1.0 - slippage)).floor() as u128;
@@ -176,20 +297,125 @@ pub async fn format_swap_details(
             "data": tx["data"].as_str().unwrap_or("none"),
             "chainId": tx["chainId"].as_u64().unwrap_or(from_chain_id),
             "gasPrice": gas_price_wei.to_string(),
-            "gas": gas_estimated.to_string()
+            "gas": gas_estimated.to_string(),
+            "maxFeePerGas": params["feeData"]["tiers"]["normal"]["maxFeePerGas"],
+            "maxPriorityFeePerGas": params["feeData"]["tiers"]["normal"]["maxPriorityFeePerGas"]
         }
     });
 
+    let mut result_mut = result.as_object().unwrap().clone();
+
+    if let Some(simulation) = simulation {
+        result_mut.insert("simulation".to_string(), simulation);
+    }
+
+    // Rebuilding the transaction starts again from what the user asked for, not the adjusted total
+    if let Some(requested) = requested_amount.filter(|requested| *requested != amount) {
+        result_mut.insert("requestedAmount".to_string(), json!(requested));
+    }
+
+    if exact_out {
+        result_mut.insert("fromAmountMax".to_string(), json!(max_amount_in(from_amount, slippage * 100.0).to_string()));
+    }
+
+    // Already included in swapCostETH, reported separately so clients can show the split
+    if let Some(fee) = l1_data_fee {
+        result_mut.insert("l1DataFee".to_string(), json!(fee.to_string()));
+    }
+
+    // What the swap costs at each EIP-1559 speed tier, next to the normal-tier swapCost above
+    if let (Some(gas), Some(tiers)) = (estimated_gas, params["feeData"]["tiers"].as_object().filter(|t| !t.is_empty())) {
+        let fee_tiers: serde_json::Map<String, Value> = tiers.iter().map(|(name, tier)| {
+            let effective_gas_price = U256::from_dec_str(tier["effectiveGasPrice"].as_str().unwrap_or("0")).unwrap_or_default();
+            let cost_eth = (gas * effective_gas_price + l1_data_fee.unwrap_or_default()).as_u128() as f64 / 1e18;
+            (name.clone(), json!({
+                "maxFeePerGas": tier["maxFeePerGas"],
+                "maxPriorityFeePerGas": tier["maxPriorityFeePerGas"],
+                "swapCostETH": format!("{:.8}", cost_eth),
+                "swapCostUSD": native_token_details["priceUSD"].as_f64()
+                    .map(|price| format!("{:.3}", cost_eth * price))
+                    .unwrap_or_else(|| "none".to_string())
+            }))
+        }).collect();
+        result_mut.insert("feeTiers".to_string(), Value::Object(fee_tiers));
+    }
+
+    // Additional fees (relayer or messaging fees) are paid in the native token on top of gas
     if let Some(fee) = additional_fee {
+        let fee_amount = U256::from_dec_str(fee.as_str().unwrap_or("0")).unwrap_or_default();
+        let fee_usd = native_token_details["priceUSD"].as_f64().map(|price| {
+            let amount = fee_amount.as_u128() as f64 / 10f64.powi(native_token_details["decimals"].as_u64().unwrap_or(18) as i32);
+            format!("{:.3}", amount * price)
+        }).unwrap_or_else(|| "none".to_string());
+
         let fee_result = json!({
-            "symbol": from_token_details["symbol"],
-            "decimals": from_token_details["decimals"],
-            "amount": fee
+            "symbol": native_token_details["symbol"],
+            "decimals": native_token_details["decimals"],
+            "amount": fee,
+            "amountUSD": fee_usd
         });
-        let mut result_mut = result.as_object().unwrap().clone();
         result_mut.insert("additionalFee".to_string(), fee_result);
-        return Ok(Value::Object(result_mut));
     }
 
-    Ok(result)
+    // Fees taken out of the bridged amount are informational, toAmount already excludes them
+    if let Some(bridge_fee) = dapp_options.and_then(|o| o["bridgeFee"].as_str()) {
+        result_mut.insert("bridgeFee".to_string(), json!({
+            "symbol": from_token_details["symbol"],
+            "decimals": from_token_details["decimals"],
+            "amount": bridge_fee
+        }));
+    }
+
+    // Expected delivery time in seconds, used when ranking quotes by speed
+    if let Some(estimated_time) = dapp_options.and_then(|o| o["estimatedTime"].as_u64()) {
+        result_mut.insert("estimatedTime".to_string(), json!(estimated_time));
+    }
+
+    // Bridges that wait for source chain finality report it so clients can estimate delivery time
+    if let Some(confirmations) = dapp_options.and_then(|o| o["confirmations"].as_u64()) {
+        result_mut.insert("confirmations".to_string(), json!(confirmations));
+    }
+
+    // Firm quotes are only honoured until their unix expiry
+    if let Some(expires_at) = dapp_options.and_then(|o| o["expiresAt"].as_u64()) {
+        result_mut.insert("expiresAt".to_string(), json!(expires_at));
+    }
+
+    Ok(QuoteResponse::from_value(Value::Object(result_mut))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_sentinel_leaves_gas_unknown() {
+        assert_eq!(adapter_gas_estimate(Some("quote")), None);
+        assert_eq!(adapter_gas_estimate(None), None);
+        assert_eq!(adapter_gas_estimate(Some("not a number")), None);
+        assert_eq!(adapter_gas_estimate(Some("210000")), Some(U256::from(210_000u64)));
+    }
+
+    #[test]
+    fn max_amount_in_is_exact_when_slippage_divides_evenly() {
+        assert_eq!(max_amount_in(U256::from(1_000u64), 0.5), U256::from(1_005u64));
+        assert_eq!(max_amount_in(U256::from(999u64), 0.0), U256::from(999u64));
+        assert_eq!(max_amount_in(U256::zero(), 1.0), U256::zero());
+    }
+
+    #[test]
+    fn max_amount_in_rounds_up_at_the_slippage_boundary() {
+        // 1001 * 1.005 = 1006.005, which must not round down to 1006
+        assert_eq!(max_amount_in(U256::from(1_001u64), 0.5), U256::from(1_007u64));
+        // A single wei with 1 bps still needs one extra wei of headroom
+        assert_eq!(max_amount_in(U256::one(), 0.01), U256::from(2u64));
+    }
+
+    #[test]
+    fn max_amount_in_rounds_slippage_to_whole_basis_points() {
+        // 0.125% is 12.5 bps, rounded to 13
+        assert_eq!(max_amount_in(U256::from(10_000u64), 0.125), U256::from(10_013u64));
+        // Negative slippage is treated as none
+        assert_eq!(max_amount_in(U256::from(10_000u64), -1.0), U256::from(10_000u64));
+    }
 }
@@ -0,0 +1,115 @@
+// src/utils/gas_cache.rs
+use dashmap::DashMap;
+use futures::future::join_all;
+use std::sync::Arc;
+use tokio::time::{interval, timeout, Duration, Instant, MissedTickBehavior};
+use crate::load_resources::AppState;
+use crate::utils::fee_oracle::{fetch_fee_estimate, FeeEstimate};
+use tracing::{debug, error};
+
+// Defaults for settings.json's gasCache, each overridable under gasCache.chains.<chainId>
+const DEFAULT_REFRESH_SECS: u64 = 12;
+const DEFAULT_MAX_AGE_SECS: u64 = 60;
+const DEFAULT_ACTIVE_SECS: u64 = 600;
+
+// A refresh that takes longer than this is dropped and retried on a later tick
+const REFRESH_TIMEOUT_SECS: u64 = 10;
+
+pub struct GasCacheEntry {
+    fees: Option<(FeeEstimate, Instant)>,
+    // Chains stop being refreshed once nobody has quoted on them for activeSecs
+    last_requested: Instant,
+}
+
+pub type GasCache = Arc<DashMap<u64, GasCacheEntry>>;
+
+struct GasCacheSettings {
+    // How often the background task refreshes an active chain
+    refresh: Duration,
+    // Cached fees older than this are fetched live instead
+    max_age: Duration,
+    active: Duration,
+}
+
+impl GasCacheSettings {
+    fn for_chain(chain_id: u64, state: &AppState) -> Self {
+        let settings = &state.settings["gasCache"];
+        let chain_settings = &settings["chains"][chain_id.to_string()];
+        let secs = |key: &str, default: u64| Duration::from_secs(
+            chain_settings[key].as_u64().or_else(|| settings[key].as_u64()).unwrap_or(default)
+        );
+
+        GasCacheSettings {
+            refresh: secs("refreshSecs", DEFAULT_REFRESH_SECS),
+            max_age: secs("maxAgeSecs", DEFAULT_MAX_AGE_SECS),
+            active: secs("activeSecs", DEFAULT_ACTIVE_SECS),
+        }
+    }
+}
+
+fn store(chain_id: u64, estimate: FeeEstimate, state: &AppState) {
+    let fetched_at = Instant::now();
+    state.gas_cache.entry(chain_id)
+        .and_modify(|entry| entry.fees = Some((estimate.clone(), fetched_at)))
+        .or_insert_with(|| GasCacheEntry {
+            fees: Some((estimate, fetched_at)),
+            last_requested: fetched_at,
+        });
+}
+
+// Fees for a quote and how old they are, from the cache unless they are missing or past maxAgeSecs
+pub async fn get_fee_estimate(chain_id: u64, state: &Arc<AppState>) -> Result<(FeeEstimate, Duration), String> {
+    let max_age = GasCacheSettings::for_chain(chain_id, state).max_age;
+
+    // The entry guard is dropped before any await, so the refresher never waits on a quote
+    {
+        let mut entry = state.gas_cache.entry(chain_id).or_insert_with(|| GasCacheEntry {
+            fees: None,
+            last_requested: Instant::now(),
+        });
+        entry.last_requested = Instant::now();
+        if let Some((estimate, fetched_at)) = &entry.fees {
+            let age = fetched_at.elapsed();
+            if age <= max_age {
+                return Ok((estimate.clone(), age));
+            }
+            debug!("Cached fees for chain {} are {}ms old, fetching live", chain_id, age.as_millis());
+        }
+    }
+
+    let estimate = fetch_fee_estimate(chain_id, state).await?;
+    store(chain_id, estimate.clone(), state);
+    Ok((estimate, Duration::ZERO))
+}
+
+// Keeps fees warm for every chain quoted within its activeSecs, each on its own refreshSecs
+pub async fn refresh_gas_cache(state: Arc<AppState>) {
+    let mut ticker = interval(Duration::from_secs(1));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+
+        let due: Vec<u64> = state.gas_cache.iter()
+            .filter(|entry| {
+                let settings = GasCacheSettings::for_chain(*entry.key(), &state);
+                let is_active = entry.last_requested.elapsed() <= settings.active;
+                let is_due = entry.fees.as_ref().is_none_or(|(_, fetched_at)| fetched_at.elapsed() >= settings.refresh);
+                is_active && is_due
+            })
+            .map(|entry| *entry.key())
+            .collect();
+
+        let refreshes = due.into_iter().map(|chain_id| {
+            let state = Arc::clone(&state);
+            async move {
+                match timeout(Duration::from_secs(REFRESH_TIMEOUT_SECS), fetch_fee_estimate(chain_id, &state)).await {
+                    Ok(Ok(estimate)) => store(chain_id, estimate, &state),
+                    Ok(Err(e)) => error!("Failed to refresh fees for chain {}: {}", chain_id, e),
+                    Err(_) => error!("Refreshing fees for chain {} timed out", chain_id),
+                }
+            }
+        });
+        join_all(refreshes).await;
+    }
+}
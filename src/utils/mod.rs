@@ -7,4 +7,8 @@ pub mod optimized_token_lookup;
 pub mod token_storage;
 pub mod balance_checker;
 pub mod allowance_checker;
-pub mod token_conversion;
\ No newline at end of file
+pub mod token_conversion;
+pub mod fee_oracle;
+pub mod rollup_fee;
+pub mod gas_cache;
+pub mod token_catalogue;
@@ -0,0 +1,202 @@
+// src/utils/rollup_fee.rs
+use ethers::abi::{Function, HumanReadableParser, Token};
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Bytes, Eip1559TransactionRequest, TransactionRequest, U256};
+use serde_json::Value;
+use std::sync::Arc;
+use std::str::FromStr;
+use crate::load_resources::AppState;
+use crate::utils::utils::get_random_rpc_proxy_provider;
+
+// Predeploys exposing getL1Fee(bytes) on each rollup family
+const OP_STACK_GAS_PRICE_ORACLE: &str = "0x420000000000000000000000000000000000000F";
+const SCROLL_L1_GAS_PRICE_ORACLE: &str = "0x5300000000000000000000000000000000000002";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum L1FeeOracle {
+    // OP Mainnet, Base, Mode and the other OP-stack chains
+    OpStack,
+    Scroll,
+}
+
+impl L1FeeOracle {
+    fn from_config(value: &str) -> Option<Self> {
+        match value {
+            "opStack" => Some(L1FeeOracle::OpStack),
+            "scroll" => Some(L1FeeOracle::Scroll),
+            _ => None,
+        }
+    }
+
+    fn address(self) -> Address {
+        let address = match self {
+            L1FeeOracle::OpStack => OP_STACK_GAS_PRICE_ORACLE,
+            L1FeeOracle::Scroll => SCROLL_L1_GAS_PRICE_ORACLE,
+        };
+        Address::from_str(address).expect("Invalid L1 fee oracle address")
+    }
+}
+
+// Fallback for configs that predate the `l1DataFee` key, used only when no chains.json entry sets it.
+// chains.json marks rollups on their entry in "chains", e.g. {"id": 10, "l1DataFee": "opStack"}:
+// "opStack" and "scroll" select the oracle, "none" opts a chain out, and chains without the key pay no L1 fee
+const DEFAULT_L1_FEE_CHAINS: &[(u64, L1FeeOracle)] = &[
+    (10, L1FeeOracle::OpStack),      // OP Mainnet
+    (8453, L1FeeOracle::OpStack),    // Base
+    (34443, L1FeeOracle::OpStack),   // Mode
+    (81457, L1FeeOracle::OpStack),   // Blast
+    (252, L1FeeOracle::OpStack),     // Fraxtal
+    (7777777, L1FeeOracle::OpStack), // Zora
+    (534352, L1FeeOracle::Scroll),
+];
+
+lazy_static::lazy_static! {
+    static ref GET_L1_FEE: Function = HumanReadableParser::parse_function("function getL1Fee(bytes) view returns (uint256)")
+        .expect("Failed to parse getL1Fee");
+}
+
+// The oracle configured for a chain in chains.json, falling back to the defaults when the file marks no chain at all
+fn configured_oracle(chains: &Value, chain_id: u64) -> Option<L1FeeOracle> {
+    let entries = chains["chains"].as_array().map(Vec::as_slice).unwrap_or_default();
+    if !entries.iter().any(|chain| chain.get("l1DataFee").is_some()) {
+        return DEFAULT_L1_FEE_CHAINS.iter()
+            .find(|(id, _)| *id == chain_id)
+            .map(|(_, oracle)| *oracle);
+    }
+
+    entries.iter()
+        .find(|chain| chain["id"].as_u64() == Some(chain_id))
+        .and_then(|chain| chain["l1DataFee"].as_str())
+        .and_then(L1FeeOracle::from_config)
+}
+
+pub fn l1_fee_oracle(chain_id: u64, state: &AppState) -> Option<L1FeeOracle> {
+    configured_oracle(&state.chains, chain_id)
+}
+
+// (maxFeePerGas, maxPriorityFeePerGas) of the normal tier, which the built transaction carries.
+// Chains without a base fee use the gas price for both
+fn normal_tier_fees(fee_data: &Value, gas_price: U256) -> (U256, U256) {
+    let normal = &fee_data["tiers"]["normal"];
+    let fee = |field: &str| normal[field].as_str().and_then(|fee| U256::from_dec_str(fee).ok());
+    fee("maxFeePerGas").zip(fee("maxPriorityFeePerGas")).unwrap_or((gas_price, gas_price))
+}
+
+// The type-2 transaction the user will sign, RLP-encoded the way it lands in the L1 batch bar the signature
+fn unsigned_payload(transaction: &Value, chain_id: u64, nonce: U256, fees: (U256, U256)) -> Result<Bytes, String> {
+    let data = transaction["data"].as_str()
+        .and_then(|data| Bytes::from_str(data).ok())
+        .ok_or("Invalid calldata")?;
+    let to = Address::from_str(transaction["to"].as_str().unwrap_or_default())
+        .map_err(|e| format!("Invalid to address: {}", e))?;
+    let value = U256::from_dec_str(transaction["value"].as_str().unwrap_or("0")).unwrap_or_default();
+    let gas = transaction["gas"].as_u64().map(U256::from)
+        .or_else(|| transaction["gas"].as_str().and_then(|gas| U256::from_dec_str(gas).ok()))
+        .unwrap_or_default();
+
+    let (max_fee_per_gas, max_priority_fee_per_gas) = fees;
+    let unsigned: TypedTransaction = Eip1559TransactionRequest::new()
+        .to(to)
+        .value(value)
+        .data(data)
+        .gas(gas)
+        .nonce(nonce)
+        .chain_id(chain_id)
+        .max_fee_per_gas(max_fee_per_gas)
+        .max_priority_fee_per_gas(max_priority_fee_per_gas)
+        .into();
+
+    Ok(unsigned.rlp())
+}
+
+// Wei the rollup charges to post this transaction to L1, None on chains without an L1 data fee
+pub async fn fetch_l1_data_fee(chain_id: u64, transaction: &Value, fee_data: &Value, gas_price: U256, state: &Arc<AppState>) -> Result<Option<U256>, String> {
+    let Some(oracle) = l1_fee_oracle(chain_id, state) else {
+        return Ok(None);
+    };
+
+    let provider = get_random_rpc_proxy_provider(chain_id, &state.rpc_proxy_providers)
+        .ok_or_else(|| format!("No RPC provider found for chain ID: {}", chain_id))?;
+
+    // The sender's next nonce, so the payload is as long as the one that gets signed
+    let nonce = match transaction["from"].as_str().and_then(|from| Address::from_str(from).ok()) {
+        Some(from) => provider.get_transaction_count(from, None).await.unwrap_or_default(),
+        None => U256::zero(),
+    };
+
+    let payload = unsigned_payload(transaction, chain_id, nonce, normal_tier_fees(fee_data, gas_price))?;
+    let call_data = GET_L1_FEE.encode_input(&[Token::Bytes(payload.to_vec())])
+        .map_err(|e| format!("Failed to encode getL1Fee: {}", e))?;
+    let call: TypedTransaction = TransactionRequest::new().to(oracle.address()).data(call_data).into();
+
+    let result = provider.call(&call, None).await
+        .map_err(|e| format!("getL1Fee call failed: {}", e))?;
+
+    let fee = GET_L1_FEE.decode_output(&result).ok()
+        .and_then(|tokens| tokens.into_iter().next())
+        .and_then(|token| token.into_uint())
+        .ok_or("Invalid getL1Fee response")?;
+
+    Ok(Some(fee))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn chains_json_marks_override_the_defaults() {
+        let chains = json!({ "chains": [
+            { "id": 10, "l1DataFee": "opStack" },
+            { "id": 8453, "l1DataFee": "none" },
+            { "id": 534352, "l1DataFee": "scroll" },
+            { "id": 1 }
+        ]});
+        assert_eq!(configured_oracle(&chains, 10), Some(L1FeeOracle::OpStack));
+        assert_eq!(configured_oracle(&chains, 534352), Some(L1FeeOracle::Scroll));
+        assert_eq!(configured_oracle(&chains, 8453), None);
+        // Listed in the defaults, but chains.json decides once it marks any chain
+        assert_eq!(configured_oracle(&chains, 34443), None);
+        assert_eq!(configured_oracle(&chains, 1), None);
+    }
+
+    #[test]
+    fn defaults_apply_when_chains_json_marks_nothing() {
+        let chains = json!({ "chains": [{ "id": 8453 }, { "id": 1 }] });
+        assert_eq!(configured_oracle(&chains, 8453), Some(L1FeeOracle::OpStack));
+        assert_eq!(configured_oracle(&chains, 534352), Some(L1FeeOracle::Scroll));
+        assert_eq!(configured_oracle(&chains, 1), None);
+        assert_eq!(configured_oracle(&Value::Null, 10), Some(L1FeeOracle::OpStack));
+    }
+
+    #[test]
+    fn payload_uses_the_normal_tier_fees() {
+        let fee_data = json!({ "tiers": { "normal": { "maxFeePerGas": "3000", "maxPriorityFeePerGas": "100" } } });
+        assert_eq!(normal_tier_fees(&fee_data, U256::from(7u64)), (U256::from(3000u64), U256::from(100u64)));
+        assert_eq!(normal_tier_fees(&json!({ "tiers": {} }), U256::from(7u64)), (U256::from(7u64), U256::from(7u64)));
+    }
+
+    #[test]
+    fn payload_is_a_type_2_transaction() {
+        let transaction = json!({
+            "to": "0x1111111111111111111111111111111111111111",
+            "data": "0xdeadbeef",
+            "value": "5",
+            "gas": 210000
+        });
+        let payload = unsigned_payload(&transaction, 10, U256::from(42u64), (U256::from(3000u64), U256::from(100u64))).unwrap();
+        assert_eq!(payload[0], 0x02);
+
+        // chainId, nonce, maxPriorityFeePerGas, maxFeePerGas, gas, to, value, data, accessList
+        let fields = ethers::utils::rlp::Rlp::new(&payload[1..]);
+        assert_eq!(fields.item_count().unwrap(), 9);
+        let field = |index: usize| fields.val_at::<U256>(index).unwrap();
+        assert_eq!(field(0), U256::from(10u64));
+        assert_eq!(field(1), U256::from(42u64));
+        assert_eq!(field(2), U256::from(100u64));
+        assert_eq!(field(3), U256::from(3000u64));
+        assert_eq!(field(4), U256::from(210_000u64));
+    }
+}
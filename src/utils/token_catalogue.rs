@@ -0,0 +1,40 @@
+// src/utils/token_catalogue.rs
+use serde_json::Value;
+
+lazy_static::lazy_static! {
+    // Layerswap's network list doubles as a price catalogue for tokens the token list can't price
+    static ref NETWORKS: Vec<Value> = {
+        let paths_str = include_str!("../dapps/abi/layerswap/paths.json");
+        let paths: Value = serde_json::from_str(paths_str).expect("Failed to parse paths.json");
+        paths["data"].as_array().expect("Invalid networks in paths.json").clone()
+    };
+}
+
+fn is_native_address(token_address: &str) -> bool {
+    let zero_address = "0x0000000000000000000000000000000000000000";
+    let eee_address = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+    let address = token_address.to_lowercase();
+    address == zero_address || address == eee_address
+}
+
+pub fn find_network(chain_id: u64) -> Option<&'static Value> {
+    NETWORKS.iter().find(|network| {
+        network["type"].as_str() == Some("evm") && network["chain_id"].as_str() == Some(chain_id.to_string().as_str())
+    })
+}
+
+// Native tokens are listed without a contract address
+pub fn find_token<'a>(network: &'a Value, token_address: &str) -> Option<&'a Value> {
+    let is_native = is_native_address(token_address);
+    network["tokens"].as_array()?.iter().find(|token| match token["contract"].as_str() {
+        Some(contract) => !is_native && contract.to_lowercase() == token_address.to_lowercase(),
+        None => is_native,
+    })
+}
+
+// USD price from the bundled catalogue, used when the token list has no price
+pub fn catalogue_price_usd(chain_id: u64, token_address: &str) -> Option<f64> {
+    find_network(chain_id)
+        .and_then(|network| find_token(network, token_address))
+        .and_then(|token| token["price_in_usd"].as_f64())
+}
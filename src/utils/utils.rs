@@ -17,7 +17,18 @@ use ethers::types::transaction::eip2718::TypedTransaction;
 use ethers::types::{Bytes, H160};
 use std::str::FromStr;
 
+// Symbiosis' id for the Bitcoin network, the only non-EVM destination we quote
+pub const BITCOIN_CHAIN_ID: u64 = 3652501241;
 
+lazy_static::lazy_static! {
+    // Legacy (1...), P2SH (3...) and bech32 (bc1...) mainnet addresses
+    static ref BITCOIN_ADDRESS_REGEX: regex::Regex =
+        regex::Regex::new(r"^(bc1[a-z0-9]{25,87}|[13][a-km-zA-HJ-NP-Z1-9]{25,34})$").unwrap();
+}
+
+pub fn is_bitcoin_address(address: &str) -> bool {
+    BITCOIN_ADDRESS_REGEX.is_match(address)
+}
 
 pub async fn call_json_rpc(chain_id: u64, to: &str, data: &[u8], providers: &RpcProxyProviderMap) -> Result<Bytes, String> {
     let provider = get_random_rpc_proxy_provider(chain_id, providers)
@@ -100,7 +111,8 @@ pub async fn fetch_gas_price(
             match provider.get_gas_price().await {
                 Ok(gas_price) => {
                     let gas_price_wei = gas_price.to_string();
-                    let gas_price_gwei = format!("{:.9}", gas_price.as_u64() as f64 / 1e9);
+                    // format_units keeps prices above u64::MAX wei from panicking
+                    let gas_price_gwei = ethers::utils::format_units(gas_price, "gwei").unwrap_or_else(|_| "0".to_string());
                     tracing::info!("Successfully fetched gas price for chain ID {}: {} wei, {} gwei", chain_id, gas_price_wei, gas_price_gwei);
                     return Ok((gas_price_wei, gas_price_gwei));
                 }
@@ -234,4 +246,26 @@ pub fn serialize_big_ints(data: &mut Value) {
         }
         _ => {}
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_mainnet_bitcoin_addresses() {
+        assert!(is_bitcoin_address("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"));
+        assert!(is_bitcoin_address("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy"));
+        assert!(is_bitcoin_address("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"));
+    }
+
+    #[test]
+    fn rejects_non_bitcoin_addresses() {
+        assert!(!is_bitcoin_address("0x1111111111111111111111111111111111111111"));
+        assert!(!is_bitcoin_address("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"));
+        // Base58 has no 0, O, I or l
+        assert!(!is_bitcoin_address("1A1zP1eP5QGefi2DMPTfTL5SLmv7Div0Na"));
+        assert!(!is_bitcoin_address("bc1qshort"));
+        assert!(!is_bitcoin_address(" 1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"));
+        assert!(!is_bitcoin_address(""));
+    }
+}